@@ -0,0 +1,10 @@
+//! Input handling for widgets that are interacted with directly rather than through a state.
+
+use crossterm::event::KeyEvent;
+
+/// A widget that can respond to key presses by changing itself.
+pub trait Handle {
+    /// Handles the key, returning whether it was used. Keys that aren't used can be passed on to
+    /// whatever else might want them.
+    fn handle_key(&mut self, key: KeyEvent) -> bool;
+}
@@ -5,7 +5,28 @@
 // TODO: #![warn(missing_docs)]
 #![warn(missing_debug_implementations)]
 
+mod handle;
+#[cfg(feature = "serde")]
+mod serde_remote;
+mod theme;
 mod widgets;
 
+pub use handle::Handle;
+pub use theme::Theme;
+pub use widgets::Divider;
+pub use widgets::List;
+pub use widgets::ListState;
 pub use widgets::Panel;
+pub use widgets::PanelBuildError;
+pub use widgets::PanelBuilder;
+pub use widgets::PanelChild;
+pub use widgets::PanelDirection;
+pub use widgets::PanelState;
+pub use widgets::PanelWidget;
+pub use widgets::ProgressBar;
 pub use widgets::Text;
+pub use widgets::TextBuilder;
+pub use widgets::TextDirection;
+pub use widgets::TextInput;
+pub use widgets::TextWrap;
+pub use widgets::VerticalAlignment;
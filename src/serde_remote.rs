@@ -0,0 +1,145 @@
+//! Serialization of the ratatui types that don't support serde themselves, used through
+//! `#[serde(with = "crate::serde_remote")]` or `#[serde(with = "crate::serde_remote::option")]`.
+//!
+//! The enums are written by name and borders as a list of the sides they're on.
+
+use std::str::FromStr;
+
+use ratatui::{
+    layout::{Alignment, Direction},
+    widgets::{BorderType, Borders, Padding, ScrollbarOrientation},
+};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+pub(crate) struct Remote<T>(T);
+
+pub(crate) fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Clone,
+    Remote<T>: Serialize,
+    S: Serializer,
+{
+    Remote(value.clone()).serialize(serializer)
+}
+
+pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    Remote<T>: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Remote::deserialize(deserializer).map(|remote| remote.0)
+}
+
+pub(crate) mod option {
+    use super::*;
+
+    pub(crate) fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Clone,
+        Remote<T>: Serialize,
+        S: Serializer,
+    {
+        value.clone().map(Remote).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        Remote<T>: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Option::<Remote<T>>::deserialize(deserializer).map(|remote| remote.map(|r| r.0))
+    }
+}
+
+/// Serializes enums that ratatui can already convert to and from strings by name.
+macro_rules! by_name {
+    ($($ty:ty),*) => {
+        $(
+            impl Serialize for Remote<$ty> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(&self.0)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for Remote<$ty> {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let name = String::deserialize(deserializer)?;
+                    <$ty>::from_str(&name).map(Remote).map_err(D::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+by_name!(Alignment, Direction, BorderType, ScrollbarOrientation);
+
+impl Serialize for Remote<Borders> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter_names().map(|(name, _)| name))
+    }
+}
+
+impl<'de> Deserialize<'de> for Remote<Borders> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .try_fold(Borders::NONE, |borders, name| {
+                Borders::from_name(name)
+                    .map(|side| borders | side)
+                    .ok_or_else(|| D::Error::custom(format!("unknown border `{name}`")))
+            })
+            .map(Remote)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Padding")]
+struct PaddingDef {
+    left: u16,
+    right: u16,
+    top: u16,
+    bottom: u16,
+}
+
+impl Serialize for Remote<Padding> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PaddingDef::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Remote<Padding> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PaddingDef::deserialize(deserializer).map(Remote)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::de::{
+        value::{Error, SeqDeserializer, StrDeserializer},
+        IntoDeserializer,
+    };
+    use tracing_test::traced_test;
+
+    #[traced_test]
+    #[test]
+    fn test_enum_by_name() {
+        let name: StrDeserializer<Error> = "Horizontal".into_deserializer();
+        assert_eq!(deserialize::<Direction, _>(name), Ok(Direction::Horizontal));
+
+        let name: StrDeserializer<Error> = "Sideways".into_deserializer();
+        assert!(deserialize::<Direction, _>(name).is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_borders_by_side() {
+        let sides: SeqDeserializer<_, Error> = vec!["TOP", "LEFT"].into_deserializer();
+        assert_eq!(
+            deserialize::<Borders, _>(sides),
+            Ok(Borders::TOP | Borders::LEFT)
+        );
+    }
+}
@@ -0,0 +1,34 @@
+//! A Theme bundles the styles used across widgets so a whole UI can share a consistent look.
+
+use ratatui::style::{Modifier, Style};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Default styles for widgets, anything set explicitly on a widget takes precedence.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Theme {
+    /// Style text is rendered with.
+    pub text_style: Style,
+    /// Background filling the inside of panels.
+    pub panel_style: Style,
+    pub border_style: Style,
+    pub title_style: Style,
+    /// Style patched over the border of a focused panel.
+    pub focus_style: Style,
+    /// Style applied over the selected child of a panel.
+    pub highlight_style: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            text_style: Style::default(),
+            panel_style: Style::default(),
+            border_style: Style::default(),
+            title_style: Style::default(),
+            focus_style: Style::new().add_modifier(Modifier::BOLD),
+            highlight_style: Style::new().add_modifier(Modifier::REVERSED),
+        }
+    }
+}
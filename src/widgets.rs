@@ -1,5 +1,15 @@
+mod divider;
+mod list;
 mod panel;
+mod progress_bar;
 mod text;
+mod text_input;
 
-pub use panel::Panel;
-pub use text::Text;
+pub use divider::Divider;
+pub use list::{List, ListState};
+pub use panel::{
+    Panel, PanelBuildError, PanelBuilder, PanelChild, PanelDirection, PanelState, PanelWidget,
+};
+pub use progress_bar::ProgressBar;
+pub use text::{Text, TextBuilder, TextDirection, TextWrap, VerticalAlignment};
+pub use text_input::TextInput;
@@ -0,0 +1,128 @@
+//! Code for a strotui Divider.
+//!
+//! A Divider is a horizontal line across its area, optionally with a label in the middle, for
+//! separating sections of a [`Panel`](super::Panel).
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::WidgetRef};
+use unicode_width::UnicodeWidthStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Divider {
+    symbol: char,
+    label: Option<String>,
+    style: Style,
+}
+
+impl Default for Divider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Divider {
+    pub fn new() -> Self {
+        Divider {
+            symbol: '─',
+            label: None,
+            style: Style::default(),
+        }
+    }
+
+    /// Text shown in the middle of the line, with a space either side of it.
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// The character the line is drawn with, `─` by default.
+    pub fn with_symbol(mut self, symbol: char) -> Self {
+        self.symbol = symbol;
+        self
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub(crate) fn get_height(&self) -> u16 {
+        1
+    }
+
+    /// Width of the label with a couple of line characters either side, or a single character
+    /// without a label.
+    pub(crate) fn get_width(&self) -> u16 {
+        self.label.as_ref().map_or(1, |label| {
+            u16::try_from(label.width() + 6).unwrap_or(u16::MAX)
+        })
+    }
+}
+
+impl WidgetRef for Divider {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        let y = area.top();
+        for x in area.left()..area.right() {
+            buf[(x, y)].set_char(self.symbol).set_style(self.style);
+        }
+
+        if let Some(label) = &self.label {
+            let label = format!(" {label} ");
+            let width = (label.width() as u16).min(area.width);
+            let x = area.left() + (area.width - width) / 2;
+            buf.set_stringn(x, y, label, width as usize, self.style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ratatui::{style::Color, widgets::Padding};
+    use tracing_test::traced_test;
+
+    use crate::widgets::{Panel, PanelWidget, Text};
+
+    #[traced_test]
+    #[test]
+    fn test_divider_between_texts() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Divider(
+                Divider::new()
+                    .with_label("Section".to_string())
+                    .with_style(Style::new().fg(Color::Yellow)),
+            ))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .build();
+
+        let rect = Rect::new(0, 0, 24, 5);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_plain_divider() {
+        let rect = Rect::new(0, 0, 8, 1);
+        let mut buffer = Buffer::empty(rect);
+
+        Divider::new()
+            .with_symbol('=')
+            .render_ref(rect, &mut buffer);
+
+        assert_eq!(buffer, Buffer::with_lines(["========"]));
+    }
+}
@@ -0,0 +1,290 @@
+//! Code for a strotui List.
+//!
+//! A List is a column of text items that can be selected and scrolled through. It's laid out by a
+//! [`Panel`] so long items wrap in the same way as any other text in a panel.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    widgets::{StatefulWidget, WidgetRef},
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Panel, PanelState, PanelWidget, Text};
+use crate::Handle;
+
+/// State of a [`List`] kept by the caller between renders.
+#[derive(Debug, Clone, Default)]
+pub struct ListState {
+    panel: PanelState,
+}
+
+impl ListState {
+    pub fn selected(&self) -> Option<usize> {
+        self.panel.selected()
+    }
+
+    /// Selects the item at `index`, it's scrolled into view the next time the list is rendered.
+    pub fn select(&mut self, index: Option<usize>) {
+        self.panel.select(index);
+    }
+
+    /// Number of rows of the items scrolled out of view at the top.
+    pub fn offset(&self) -> u16 {
+        self.panel.scroll_offset()
+    }
+
+    /// Scrolls by `delta` rows, negative being up, stopping at either end of the items.
+    pub fn scroll_by(&mut self, delta: i32) {
+        self.panel.scroll_by(delta);
+    }
+}
+
+/// A list of text items, one of which can be selected.
+///
+/// When rendered with a [`ListState`] the selection is kept in the state, otherwise the list's own
+/// selection is shown. The latter is how a list nested in a [`Panel`] shows what's selected.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct List<'a> {
+    panel: Panel<'a>,
+    selected: Option<usize>,
+}
+
+impl List<'_> {
+    pub fn new(items: Vec<String>) -> Self {
+        Self::from_texts(items.into_iter().map(Text::new).collect())
+    }
+
+    pub fn from_texts(items: Vec<Text>) -> Self {
+        let panel = items
+            .into_iter()
+            .fold(Panel::new_builder(None), |builder, item| {
+                builder.add_child(PanelWidget::Text(item))
+            })
+            .build();
+        List {
+            panel,
+            selected: None,
+        }
+    }
+
+    /// Style applied over the selected item, reverse video by default.
+    pub fn with_highlight_style(mut self, style: Style) -> Self {
+        self.panel.set_highlight_style(style);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.panel.child_count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Selects the item at `index` for when the list is rendered without a state.
+    pub fn select(&mut self, index: Option<usize>) {
+        self.selected = index;
+    }
+
+    /// Selects the item after the selected one, going back round to the first after the last.
+    pub fn select_next(&mut self) {
+        self.selected = match (self.selected, self.len()) {
+            (_, 0) => None,
+            (Some(index), len) => Some((index + 1) % len),
+            (None, _) => Some(0),
+        };
+    }
+
+    /// Selects the item before the selected one, going round to the last before the first.
+    pub fn select_previous(&mut self) {
+        self.selected = match (self.selected, self.len()) {
+            (_, 0) => None,
+            (Some(index), len) => Some((index.min(len - 1) + len - 1) % len),
+            (None, len) => Some(len - 1),
+        };
+    }
+
+    /// The width and height the list takes up when given at most `max_width` columns.
+    pub fn measure(&self, max_width: u16) -> (u16, u16) {
+        self.panel.measure(max_width)
+    }
+
+    pub(crate) fn get_height(&self, width: u16) -> u16 {
+        self.panel.get_height(width)
+    }
+
+    pub(crate) fn get_width(&self) -> u16 {
+        self.panel.get_width()
+    }
+
+    /// Renders the rows of the list from `skip` down, see [`Panel::render_rows`].
+    pub(crate) fn render_rows(&self, area: Rect, skip: u16, buf: &mut Buffer) {
+        let mut state = ListState::default();
+        state.select(self.selected);
+        self.panel.render_rows(area, skip, buf, &state.panel);
+    }
+}
+
+/// Moves the list's own selection with Up and Down, going round at either end.
+impl Handle for List<'_> {
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Up => self.select_previous(),
+            KeyCode::Down => self.select_next(),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+impl WidgetRef for List<'_> {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let mut state = ListState::default();
+        state.select(self.selected);
+        StatefulWidget::render(self, area, buf, &mut state);
+    }
+}
+
+impl StatefulWidget for &List<'_> {
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(&self.panel, area, buf, &mut state.panel);
+    }
+}
+
+impl StatefulWidget for List<'_> {
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(&self, area, buf, state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ratatui::style::{Color, Modifier};
+    use tracing_test::traced_test;
+
+    fn items(count: usize) -> Vec<String> {
+        (1..=count).map(|i| format!("Item {i}")).collect()
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_selection() {
+        let list = List::new(items(3)).with_highlight_style(Style::new().bg(Color::Blue));
+        let rect = Rect::new(0, 0, 10, 4);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = ListState::default();
+        state.select(Some(1));
+
+        StatefulWidget::render(&list, rect, &mut buffer, &mut state);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrolled_to_selection() {
+        let list = List::new(items(10));
+        let rect = Rect::new(0, 0, 10, 4);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = ListState::default();
+        state.select(Some(7));
+
+        StatefulWidget::render(&list, rect, &mut buffer, &mut state);
+
+        assert_eq!(state.offset(), 4);
+        assert_eq!(buffer[(0, 3)].modifier, Modifier::REVERSED);
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_long_items_wrap() {
+        let list = List::new(vec![
+            "A long item that wraps".to_string(),
+            "Short".to_string(),
+        ]);
+        let rect = Rect::new(0, 0, 12, 4);
+        let mut buffer = Buffer::empty(rect);
+
+        list.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_select_wraps_around() {
+        let mut list = List::new(items(3));
+
+        list.select_next();
+        assert_eq!(list.selected(), Some(0));
+        list.select_next();
+        list.select_next();
+        list.select_next();
+        assert_eq!(list.selected(), Some(0));
+        list.select_previous();
+        assert_eq!(list.selected(), Some(2));
+
+        list.select(None);
+        list.select_previous();
+        assert_eq!(list.selected(), Some(2));
+
+        let mut empty = List::new(vec![]);
+        empty.select_next();
+        assert_eq!(empty.selected(), None);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_highlight_in_panel() {
+        let mut list = List::new(items(3)).with_highlight_style(Style::new().bg(Color::Blue));
+        list.select(Some(2));
+        let panel = Panel::new_builder(Some("List".to_string()))
+            .add_child(PanelWidget::Text(Text::from("Pick one")))
+            .add_child(PanelWidget::List(list))
+            .build();
+        let rect = Rect::new(0, 0, 14, 8);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        assert_eq!(buffer[(3, 5)].bg, Color::Blue);
+        assert_eq!(buffer[(3, 4)].bg, Color::Reset);
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_handle_key_selects() {
+        let mut list = List::new(items(3));
+
+        assert!(list.handle_key(KeyEvent::from(KeyCode::Down)));
+        assert!(list.handle_key(KeyEvent::from(KeyCode::Down)));
+        assert_eq!(list.selected(), Some(1));
+        assert!(list.handle_key(KeyEvent::from(KeyCode::Up)));
+        assert!(list.handle_key(KeyEvent::from(KeyCode::Up)));
+        assert_eq!(list.selected(), Some(2));
+        assert!(!list.handle_key(KeyEvent::from(KeyCode::Enter)));
+        assert_eq!(list.selected(), Some(2));
+    }
+}
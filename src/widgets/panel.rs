@@ -3,201 +3,2968 @@
 //! A Panel is a container that can hold other widgets. It can be scrolled and you can optionally
 //! select things within it.
 
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    ops::Range,
+    rc::Rc,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
-    widgets::{Block, Borders, Padding, Scrollbar, ScrollbarState, StatefulWidget, WidgetRef},
+    layout::{Alignment, Direction, Position, Rect},
+    style::Style,
+    text::Line,
+    widgets::{
+        Block, BorderType, Borders, Padding, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        StatefulWidget, WidgetRef,
+    },
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Divider, List, Text, VerticalAlignment};
+use crate::{Handle, Theme};
+
+/// A widget that can be placed in a [`Panel`] through [`PanelWidget::Custom`].
+///
+/// ```
+/// use ratatui::{buffer::Buffer, layout::Rect, widgets::WidgetRef};
+/// use strotui::{Panel, PanelChild, PanelWidget};
+///
+/// /// Fills its whole area with a character.
+/// #[derive(Debug)]
+/// struct Fill(char, u16);
+///
+/// impl WidgetRef for Fill {
+///     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+///         for position in area.positions() {
+///             buf[position].set_char(self.0);
+///         }
+///     }
+/// }
+///
+/// impl PanelChild for Fill {
+///     fn measured_height(&self, _width: u16) -> u16 {
+///         self.1
+///     }
+/// }
+///
+/// let panel = Panel::new_builder(None)
+///     .add_child(PanelWidget::Custom(Box::new(Fill('#', 2))))
+///     .build();
+/// ```
+pub trait PanelChild: WidgetRef + std::fmt::Debug {
+    /// Number of rows the widget needs when laid out at `width` columns.
+    fn measured_height(&self, width: u16) -> u16;
 
-use super::Text;
+    /// Number of columns the widget would like when laid out horizontally, by default all the
+    /// width that's left.
+    fn measured_width(&self) -> u16 {
+        u16::MAX
+    }
+}
 
+/// A child of a [`Panel`], with the `serde` feature everything but custom children can be
+/// serialized.
 #[derive(Debug)]
-pub enum PanelWidget {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PanelWidget<'a> {
     Text(Text),
+    Panel(Panel<'a>),
+    Divider(Divider),
+    List(List<'a>),
+    /// A blank gap of a fixed number of rows, showing the panel's background.
+    Spacer(u16),
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Box<dyn PanelChild + 'a>),
 }
 
-impl PanelWidget {
+impl PanelWidget<'_> {
     fn get_height(&self, width: u16) -> u16 {
         match self {
             PanelWidget::Text(text) => text.get_height(width),
+            PanelWidget::Panel(panel) => panel.get_height(width),
+            PanelWidget::Divider(divider) => divider.get_height(),
+            PanelWidget::List(list) => list.get_height(width),
+            PanelWidget::Spacer(height) => *height,
+            PanelWidget::Custom(widget) => widget.measured_height(width),
+        }
+    }
+
+    /// Width and height the widget takes up when given at most `max_width` columns.
+    fn measure(&self, max_width: u16) -> (u16, u16) {
+        match self {
+            PanelWidget::Text(text) => text.measure(max_width),
+            PanelWidget::Panel(panel) => panel.measure(max_width),
+            PanelWidget::Divider(divider) => {
+                (divider.get_width().min(max_width), divider.get_height())
+            }
+            PanelWidget::List(list) => list.measure(max_width),
+            PanelWidget::Spacer(height) => (0, *height),
+            PanelWidget::Custom(widget) => (
+                widget.measured_width().min(max_width),
+                widget.measured_height(max_width),
+            ),
+        }
+    }
+
+    /// Width the widget would like when laid out side by side with others, for text this is the
+    /// width of its longest line without any wrapping.
+    pub fn get_width(&self) -> u16 {
+        match self {
+            PanelWidget::Text(text) => text.get_width(),
+            PanelWidget::Panel(panel) => panel.get_width(),
+            PanelWidget::Divider(divider) => divider.get_width(),
+            PanelWidget::List(list) => list.get_width(),
+            PanelWidget::Spacer(_) => 0,
+            PanelWidget::Custom(widget) => widget.measured_width(),
+        }
+    }
+}
+
+/// Position and size of a child within the combined children of a [`Panel`].
+#[derive(Debug, Clone, Copy)]
+struct ChildExtent {
+    x: usize,
+    y: usize,
+    width: u16,
+    height: usize,
+}
+
+impl ChildExtent {
+    /// Where the child is along the direction the children are laid out in.
+    fn along(&self, direction: PanelDirection) -> Range<usize> {
+        match direction {
+            PanelDirection::Vertical => self.y..self.y + self.height,
+            PanelDirection::Horizontal => self.x..self.x + self.width as usize,
+        }
+    }
+}
+
+impl WidgetRef for PanelWidget<'_> {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        match self {
+            PanelWidget::Text(text) => text.render_ref(area, buf),
+            PanelWidget::Panel(panel) => panel.render_ref(area, buf),
+            PanelWidget::Divider(divider) => divider.render_ref(area, buf),
+            PanelWidget::List(list) => list.render_ref(area, buf),
+            PanelWidget::Spacer(_) => {}
+            PanelWidget::Custom(widget) => widget.render_ref(area, buf),
+        }
+    }
+}
+
+impl PanelWidget<'_> {
+    /// Renders the widget as it would be rendered into an area `skip` rows taller than `area`,
+    /// with those rows cut off the top. A buffer can't hold more than `u16::MAX` cells, so text and
+    /// panels too tall for one render just the rows that are shown.
+    fn render_rows(&self, area: Rect, skip: u16, buf: &mut Buffer) {
+        let height = skip.saturating_add(area.height);
+        let fits = area.width as usize * height as usize <= u16::MAX as usize;
+        match self {
+            PanelWidget::Text(text) => text.render_rows(area, skip, buf),
+            PanelWidget::Panel(panel) if !fits => {
+                panel.render_rows(area, skip, buf, &PanelState::default())
+            }
+            PanelWidget::List(list) if !fits => list.render_rows(area, skip, buf),
+            _ if skip == 0 => self.render_ref(area, buf),
+            _ => {
+                // Anything else is rendered in full with the rows above cut off, as far down as a
+                // buffer can go.
+                let full = Rect {
+                    x: 0,
+                    y: 0,
+                    width: area.width,
+                    height: height.min(u16::MAX / area.width.max(1)),
+                };
+                let mut scratch = Buffer::empty(full);
+                self.render_ref(full, &mut scratch);
+                for row in skip..full.height {
+                    for col in 0..area.width {
+                        buf[(area.x + col, area.y + row - skip)] = scratch[(col, row)].clone();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// State of a [`Panel`] kept by the caller between renders. Like ratatui's own widget states it's
+/// set up with the `with_` methods, and parts of it are worked out when it's rendered.
+///
+/// ```
+/// use strotui::PanelState;
+///
+/// let state = PanelState::default().with_selected(Some(2)).with_focused(true);
+/// assert_eq!(state.selected(), Some(2));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PanelState {
+    /// Number of rows of the combined children scrolled out of view at the top.
+    scroll_offset: u16,
+    /// Number of columns of the combined children scrolled out of view on the left.
+    scroll_offset_x: u16,
+    /// Index of the selected child, if any.
+    selected: Option<usize>,
+    /// Whether the panel has focus. Only a focused panel handles key events, and its border is
+    /// drawn with the focus style.
+    focused: bool,
+    /// Furthest the panel could scroll as of the last render, unknown until rendered once.
+    max_scroll_offset: Option<u16>,
+    /// Furthest the panel could scroll sideways as of the last render.
+    max_scroll_offset_x: Option<u16>,
+    /// Rows of children shown as of the last render.
+    display_height: u16,
+    /// Selection as of the last render, so the selection is only scrolled to when it changes.
+    rendered_selected: Option<usize>,
+}
+
+impl PanelState {
+    /// Start scrolled so `offset` rows are hidden at the top, this is kept within the content when
+    /// the panel is rendered.
+    pub fn with_scroll_offset(mut self, offset: u16) -> Self {
+        self.scroll_offset = offset;
+        self
+    }
+
+    /// Start scrolled so `offset` columns are hidden on the left.
+    pub fn with_scroll_offset_x(mut self, offset: u16) -> Self {
+        self.scroll_offset_x = offset;
+        self
+    }
+
+    pub fn with_selected(mut self, selected: Option<usize>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn with_focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    /// Number of rows of the combined children scrolled out of view at the top.
+    pub fn scroll_offset(&self) -> u16 {
+        self.scroll_offset
+    }
+
+    /// Number of columns of the combined children scrolled out of view on the left.
+    pub fn scroll_offset_x(&self) -> u16 {
+        self.scroll_offset_x
+    }
+
+    /// Index of the selected child, if any.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Select the child at `index`, or nothing. The selection is scrolled into view the next time
+    /// the panel is rendered.
+    pub fn select(&mut self, index: Option<usize>) {
+        self.selected = index;
+    }
+
+    /// Whether the panel has focus. Only a focused panel handles key events, and its border is
+    /// drawn with the focus style.
+    pub fn focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Scrolls by `delta` rows, negative being up, stopping at either end of the content.
+    pub fn scroll_by(&mut self, delta: i32) {
+        let offset = (self.scroll_offset as i32).saturating_add(delta).max(0);
+        self.scroll_to(u16::try_from(offset).unwrap_or(u16::MAX));
+    }
+
+    /// Scrolls so that `offset` rows are hidden at the top, as far as the content allows.
+    pub fn scroll_to(&mut self, offset: u16) {
+        self.scroll_offset = offset.min(self.max_scroll_offset.unwrap_or(u16::MAX));
+    }
+
+    /// Scrolls sideways by `delta` columns, negative being left, stopping at either end.
+    pub fn scroll_x_by(&mut self, delta: i32) {
+        let offset = (self.scroll_offset_x as i32).saturating_add(delta).max(0);
+        self.scroll_x_to(u16::try_from(offset).unwrap_or(u16::MAX));
+    }
+
+    /// Scrolls sideways so that `offset` columns are hidden on the left, as far as the content
+    /// allows.
+    pub fn scroll_x_to(&mut self, offset: u16) {
+        self.scroll_offset_x = offset.min(self.max_scroll_offset_x.unwrap_or(u16::MAX));
+    }
+}
+
+/// Why [`PanelBuilder::try_build`] couldn't build a panel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PanelBuildError {
+    /// Scrollbar options were set but the scrollbar is turned off.
+    ScrollbarDisabled,
+    /// A horizontal scrollbar was asked for but the children are laid out vertically, so they
+    /// never need scrolling sideways.
+    HorizontalScrollbarOnVerticalPanel,
+    /// A scrollbar was asked for on a side of the panel without a border or padding to draw it
+    /// in.
+    NoRoomForScrollbar,
+}
+
+impl fmt::Display for PanelBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PanelBuildError::ScrollbarDisabled => {
+                write!(f, "scrollbar options set with the scrollbar turned off")
+            }
+            PanelBuildError::HorizontalScrollbarOnVerticalPanel => {
+                write!(f, "horizontal scrollbar on a panel laid out vertically")
+            }
+            PanelBuildError::NoRoomForScrollbar => {
+                write!(f, "no border or padding for the scrollbar to be drawn in")
+            }
         }
     }
 }
 
+impl std::error::Error for PanelBuildError {}
+
+/// Which way a [`Panel`] lays out its children, either stacked top to bottom or side by side
+/// from left to right.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PanelDirection {
+    #[default]
+    Vertical,
+    Horizontal,
+}
+
+impl From<Direction> for PanelDirection {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Vertical => PanelDirection::Vertical,
+            Direction::Horizontal => PanelDirection::Horizontal,
+        }
+    }
+}
+
+impl From<PanelDirection> for Direction {
+    fn from(direction: PanelDirection) -> Self {
+        match direction {
+            PanelDirection::Vertical => Direction::Vertical,
+            PanelDirection::Horizontal => Direction::Horizontal,
+        }
+    }
+}
+
+const DEFAULT_PADDING: Padding = Padding::symmetric(2, 1);
+
+/// Number of widths the layout of a panel's children is kept for. Rendering lays the children
+/// out both with and without room for a scrollbar.
+const LAYOUT_CACHE_SIZE: usize = 2;
+
 #[derive(Debug, Default)]
-pub struct PanelBuilder {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PanelBuilder<'a> {
     pub title: Option<String>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::option"))]
+    pub title_alignment: Option<Alignment>,
+    pub title_style: Option<Style>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::option"))]
     pub borders: Option<Borders>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::option"))]
+    pub border_type: Option<BorderType>,
+    pub border_style: Option<Style>,
+    pub focus_style: Option<Style>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::option"))]
     pub padding: Option<Padding>,
-    pub scrollbar: bool,
-    pub children: Vec<PanelWidget>,
+    pub scrollbar: Option<bool>,
+    pub scrollbar_auto: Option<bool>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::option"))]
+    pub scrollbar_orientation: Option<ScrollbarOrientation>,
+    pub scroll_offset: u16,
+    pub direction: Option<PanelDirection>,
+    pub spacing: u16,
+    pub vertical_alignment: Option<VerticalAlignment>,
+    pub style: Option<Style>,
+    pub highlight_style: Option<Style>,
+    pub theme: Option<Theme>,
+    pub children: Vec<PanelWidget<'a>>,
 }
 
-impl PanelBuilder {
+impl<'a> PanelBuilder<'a> {
     pub fn title(mut self, title: String) -> Self {
         self.title = Some(title);
         self
     }
 
+    /// Where the title sits along the top border, left by default.
+    pub fn title_alignment(mut self, alignment: Alignment) -> Self {
+        self.title_alignment = Some(alignment);
+        self
+    }
+
+    /// Style of the title text, separate from the border around it.
+    pub fn title_style(mut self, style: Style) -> Self {
+        self.title_style = Some(style);
+        self
+    }
+
     pub fn borders(mut self, borders: Borders) -> Self {
         self.borders = Some(borders);
         self
     }
 
+    /// The lines the border is drawn with, plain single lines by default.
+    pub fn border_type(mut self, border_type: BorderType) -> Self {
+        self.border_type = Some(border_type);
+        self
+    }
+
+    pub fn border_style(mut self, style: Style) -> Self {
+        self.border_style = Some(style);
+        self
+    }
+
+    /// Style patched over the border style while the panel is focused, bold by default.
+    pub fn focus_style(mut self, style: Style) -> Self {
+        self.focus_style = Some(style);
+        self
+    }
+
     pub fn padding(mut self, padding: Padding) -> Self {
         self.padding = Some(padding);
         self
     }
 
-    pub fn add_child(mut self, widget: PanelWidget) -> Self {
+    pub fn padding_top(mut self, top: u16) -> Self {
+        self.padding_mut().top = top;
+        self
+    }
+
+    pub fn padding_bottom(mut self, bottom: u16) -> Self {
+        self.padding_mut().bottom = bottom;
+        self
+    }
+
+    /// Sets just the left padding, keeping whatever the other sides are so far. The other
+    /// `padding_*` methods work the same way.
+    ///
+    /// ```
+    /// use ratatui::widgets::Padding;
+    /// use strotui::Panel;
+    ///
+    /// let builder = Panel::new_builder(Some("Title".to_string()))
+    ///     .padding_left(4)
+    ///     .padding_top(1);
+    ///
+    /// assert_eq!(builder.padding, Some(Padding::new(4, 2, 1, 1)));
+    /// ```
+    pub fn padding_left(mut self, left: u16) -> Self {
+        self.padding_mut().left = left;
+        self
+    }
+
+    pub fn padding_right(mut self, right: u16) -> Self {
+        self.padding_mut().right = right;
+        self
+    }
+
+    pub fn padding_uniform(mut self, padding: u16) -> Self {
+        self.padding = Some(Padding::uniform(padding));
+        self
+    }
+
+    fn padding_mut(&mut self) -> &mut Padding {
+        self.padding.get_or_insert(DEFAULT_PADDING)
+    }
+
+    /// Whether the panel can show a scrollbar, on by default. Along with `scrollbar_auto` this
+    /// means a scrollbar is only shown when the children don't fit.
+    pub fn scrollbar(mut self, enabled: bool) -> Self {
+        self.scrollbar = Some(enabled);
+        self
+    }
+
+    /// Whether the scrollbar is only shown when the children don't fit, on by default. When off
+    /// the scrollbar is always shown.
+    pub fn scrollbar_auto(mut self, scrollbar_auto: bool) -> Self {
+        self.scrollbar_auto = Some(scrollbar_auto);
+        self
+    }
+
+    /// Which edge the scrollbar is drawn along, down the right by default. A horizontal scrollbar
+    /// scrolls the children sideways.
+    pub fn scrollbar_orientation(mut self, orientation: ScrollbarOrientation) -> Self {
+        self.scrollbar_orientation = Some(orientation);
+        self
+    }
+
+    /// Number of rows of the combined children scrolled out of view at the top.
+    pub fn scroll_offset(mut self, scroll_offset: u16) -> Self {
+        self.scroll_offset = scroll_offset;
+        self
+    }
+
+    /// Whether children are stacked top to bottom, the default, or placed left to right.
+    pub fn direction(mut self, direction: impl Into<PanelDirection>) -> Self {
+        self.direction = Some(direction.into());
+        self
+    }
+
+    /// Number of blank rows, or columns when horizontal, left between adjacent children.
+    pub fn spacing(mut self, spacing: u16) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Where the children sit when they're shorter than the inside of the panel, top by default.
+    pub fn vertical_alignment(mut self, alignment: VerticalAlignment) -> Self {
+        self.vertical_alignment = Some(alignment);
+        self
+    }
+
+    /// Style filling the inside of the panel, children are drawn on top of it.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Style applied over the selected child, reverse video by default.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = Some(style);
+        self
+    }
+
+    /// Styles used for anything that isn't styled explicitly.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    pub fn add_child(mut self, widget: PanelWidget<'a>) -> Self {
         self.children.push(widget);
         self
     }
 
-    pub fn build<'a>(self) -> Panel<'a> {
+    /// Builds the panel as `build` does, but first checks the options make sense together.
+    pub fn try_build(self) -> Result<Panel<'a>, PanelBuildError> {
+        let scrollbar_options =
+            self.scrollbar_auto.is_some() || self.scrollbar_orientation.is_some();
+        if self.scrollbar == Some(false) && scrollbar_options {
+            return Err(PanelBuildError::ScrollbarDisabled);
+        }
+
+        let horizontal_scrollbar = matches!(
+            self.scrollbar_orientation,
+            Some(ScrollbarOrientation::HorizontalBottom | ScrollbarOrientation::HorizontalTop)
+        );
+        let vertical = matches!(self.direction, None | Some(PanelDirection::Vertical));
+        if self.scrollbar != Some(false) && horizontal_scrollbar && vertical {
+            return Err(PanelBuildError::HorizontalScrollbarOnVerticalPanel);
+        }
+
+        if !self.scrollbar_has_room() {
+            return Err(PanelBuildError::NoRoomForScrollbar);
+        }
+
+        Ok(self.build())
+    }
+
+    /// The borders and padding of the block around the panel, or `None` if it won't have one.
+    /// Titled panels have borders unless told otherwise, other panels only have a block at all if
+    /// they're given borders or padding.
+    fn block_insets(&self) -> Option<(Borders, Padding)> {
+        if self.title.is_none() && self.borders.is_none() && self.padding.is_none() {
+            return None;
+        }
+
+        let default_borders = if self.title.is_some() {
+            Borders::ALL
+        } else {
+            Borders::NONE
+        };
+        Some((
+            self.borders.unwrap_or(default_borders),
+            self.padding.unwrap_or(DEFAULT_PADDING),
+        ))
+    }
+
+    /// Whether the scrollbar, which is on unless it's turned off, has a border or padding on its
+    /// side to be drawn in rather than having to take space from the children.
+    fn scrollbar_has_room(&self) -> bool {
+        if self.scrollbar == Some(false) {
+            return true;
+        }
+
+        let Some((borders, padding)) = self.block_insets() else {
+            return false;
+        };
+        let (side, room) = match self
+            .scrollbar_orientation
+            .as_ref()
+            .unwrap_or(&ScrollbarOrientation::VerticalRight)
+        {
+            ScrollbarOrientation::VerticalRight => (Borders::RIGHT, padding.right),
+            ScrollbarOrientation::VerticalLeft => (Borders::LEFT, padding.left),
+            ScrollbarOrientation::HorizontalBottom => (Borders::BOTTOM, padding.bottom),
+            ScrollbarOrientation::HorizontalTop => (Borders::TOP, padding.top),
+        };
+        borders.contains(side) || room > 0
+    }
+
+    /// Builds the panel, any options that don't make sense together are used as best they can be.
+    /// See `try_build` to check for them instead.
+    pub fn build(self) -> Panel<'a> {
+        let block_insets = self.block_insets();
+        let theme = self.theme.unwrap_or_default();
         Panel {
-            block: self.title.map(|t| {
-                Block::default()
-                    .title(t)
-                    .borders(self.borders.unwrap_or(Borders::ALL))
-                    .padding(self.padding.unwrap_or(Padding::symmetric(2, 1)))
+            block: block_insets.map(|(borders, padding)| PanelBlock {
+                title: self.title,
+                title_alignment: self.title_alignment.unwrap_or(Alignment::Left),
+                title_style: self.title_style.unwrap_or(theme.title_style),
+                borders,
+                border_type: self.border_type.unwrap_or_default(),
+                border_style: self.border_style.unwrap_or(theme.border_style),
+                focus_style: self.focus_style.unwrap_or(theme.focus_style),
+                padding,
             }),
-            scrollbar: self.scrollbar,
+            scrollbar: self.scrollbar.unwrap_or(true),
+            scrollbar_auto: self.scrollbar_auto.unwrap_or(true),
+            scrollbar_orientation: self
+                .scrollbar_orientation
+                .unwrap_or(ScrollbarOrientation::VerticalRight),
+            scroll_offset: self.scroll_offset,
+            direction: self.direction.unwrap_or_default(),
+            spacing: self.spacing,
+            vertical_alignment: self.vertical_alignment.unwrap_or_default(),
+            style: self.style.unwrap_or(theme.panel_style),
+            highlight_style: self.highlight_style.unwrap_or(theme.highlight_style),
             children: self.children,
+            layout_cache: RefCell::default(),
+            max_scroll_offset: Cell::default(),
         }
     }
 }
 
+/// The block drawn around a [`Panel`] with a title. This is kept rather than the `Block` itself
+/// so the panel can be serialized.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct PanelBlock {
+    title: Option<String>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote"))]
+    title_alignment: Alignment,
+    title_style: Style,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote"))]
+    borders: Borders,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote"))]
+    border_type: BorderType,
+    border_style: Style,
+    focus_style: Style,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote"))]
+    padding: Padding,
+}
+
+impl PanelBlock {
+    fn block(&self, focused: bool) -> Block<'_> {
+        let border_style = if focused {
+            self.border_style.patch(self.focus_style)
+        } else {
+            self.border_style
+        };
+        let block = match &self.title {
+            Some(title) => Block::default().title(Line::styled(title.as_str(), self.title_style)),
+            None => Block::default(),
+        };
+        block
+            .title_alignment(self.title_alignment)
+            .borders(self.borders)
+            .border_type(self.border_type)
+            .border_style(border_style)
+            .padding(self.padding)
+    }
+}
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Panel<'a> {
-    block: Option<Block<'a>>,
+    block: Option<PanelBlock>,
     scrollbar: bool,
-    // TODO: style: Style,
-    children: Vec<PanelWidget>,
+    scrollbar_auto: bool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote"))]
+    scrollbar_orientation: ScrollbarOrientation,
+    scroll_offset: u16,
+    direction: PanelDirection,
+    spacing: u16,
+    vertical_alignment: VerticalAlignment,
+    style: Style,
+    highlight_style: Style,
+    children: Vec<PanelWidget<'a>>,
+    // Layouts of the children from the most recent widths they were laid out at, measuring every
+    // child is slow when there are lots of them and it's needed several times per render.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    layout_cache: RefCell<Vec<(u16, Rc<[ChildExtent]>)>>,
+    // Furthest the panel could scroll as of the last render without a `PanelState`, unknown until
+    // rendered once or after the children change.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    max_scroll_offset: Cell<Option<u16>>,
 }
 
 impl<'a> Panel<'a> {
-    pub fn new_builder(title: Option<String>) -> PanelBuilder {
+    pub fn new_builder(title: Option<String>) -> PanelBuilder<'a> {
         PanelBuilder {
             title,
+            title_alignment: None,
+            title_style: None,
             borders: None,
+            border_type: None,
+            border_style: None,
+            focus_style: None,
             padding: None,
-            scrollbar: true,
+            scrollbar: None,
+            scrollbar_auto: None,
+            scrollbar_orientation: None,
+            scroll_offset: 0,
+            direction: None,
+            spacing: 0,
+            vertical_alignment: None,
+            style: None,
+            highlight_style: None,
+            theme: None,
             children: Vec::new(),
         }
     }
 
-    pub fn add_text(&'a mut self, text: Text) {
+    pub fn children(&self) -> &[PanelWidget<'a>] {
+        &self.children
+    }
+
+    pub fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn child(&self, index: usize) -> Option<&PanelWidget<'a>> {
+        self.children.get(index)
+    }
+
+    pub(crate) fn set_highlight_style(&mut self, style: Style) {
+        self.highlight_style = style;
+    }
+
+    pub fn add_text(&mut self, text: Text) {
         self.children.push(PanelWidget::Text(text));
+        self.invalidate_layout_cache();
     }
 
-    fn render_outer(&self, area: Rect, buf: &mut Buffer) -> Rect {
-        if let Some(block) = self.block.as_ref() {
-            block.render_ref(area, buf);
-            block.inner(area)
-        } else {
-            area
+    /// Inserts a child before the one at `index`, or at the end if `index` is past the end.
+    pub fn insert_child(&mut self, index: usize, widget: PanelWidget<'a>) {
+        self.children.insert(index.min(self.children.len()), widget);
+        self.invalidate_layout_cache();
+    }
+
+    /// Removes and returns the child at `index`, shifting later children up. A selection left past
+    /// the end is moved back onto the last child the next time the panel is rendered.
+    pub fn remove_child(&mut self, index: usize) -> Option<PanelWidget<'a>> {
+        let removed = (index < self.children.len()).then(|| self.children.remove(index));
+        self.invalidate_layout_cache();
+        removed
+    }
+
+    pub fn clear_children(&mut self) {
+        self.children.clear();
+        self.invalidate_layout_cache();
+    }
+
+    /// Scrolls so `offset` rows of the children are hidden at the top when the panel is rendered
+    /// without a [`PanelState`], as far as the content allowed when it was last rendered.
+    pub fn scroll_to(&mut self, offset: u16) {
+        self.scroll_offset = offset.min(self.max_scroll_offset.get().unwrap_or(u16::MAX));
+    }
+
+    /// Scrolls by `delta` rows from the current offset, negative being up, stopping at either end
+    /// of the content.
+    pub fn scroll_by(&mut self, delta: i16) {
+        self.scroll_to(self.scroll_offset.saturating_add_signed(delta));
+    }
+
+    /// Scrolls by as little as possible so the child at `index` is in view when the panel is
+    /// rendered into `area` without a [`PanelState`], showing its top when it is taller than the
+    /// display.
+    pub fn ensure_visible(&mut self, index: usize, area: Rect) {
+        let (inner, _) = self.children_area(area);
+        let Some(ChildExtent { y, height, .. }) = self.layout(inner.width).get(index).copied()
+        else {
+            return;
+        };
+
+        let offset = self.scroll_offset as usize;
+        if let Some(offset) = offset_showing(y, height, offset, inner.height as usize) {
+            self.scroll_offset = offset;
         }
     }
 
-    fn render_children(&self, area: Rect, buf: &mut Buffer) -> usize {
-        let mut y = area.top() as usize;
-        let width = area.right() - area.left();
-        tracing::trace!("area {:?}", area);
+    /// Must be called whenever the children are changed.
+    fn invalidate_layout_cache(&mut self) {
+        self.layout_cache.get_mut().clear();
+        self.max_scroll_offset.set(None);
+    }
 
-        for child in &self.children {
-            let height = child.get_height(width).min(area.bottom() - y as u16);
+    /// Moves the selection with Up/Down and scrolls with PageUp/PageDown and Home/End, returning
+    /// whether the key was used. Keys are ignored unless the panel is focused.
+    pub fn handle_key_event(&self, state: &mut PanelState, key: KeyEvent) -> bool {
+        if !state.focused || key.kind == KeyEventKind::Release {
+            return false;
+        }
 
-            if y <= area.bottom() as usize {
-                let child_area = Rect::new(area.left(), y as u16, width, height);
-                tracing::trace!("Rendering child in area {:?}", child_area);
-                match child {
-                    PanelWidget::Text(text) => text.render_ref(child_area, buf),
-                }
+        let last = self.children.len().checked_sub(1);
+        let page = state.display_height.max(1) as i32;
+        match key.code {
+            KeyCode::Up => {
+                let Some(last) = last else { return false };
+                state.selected = Some(state.selected.map_or(last, |i| i.saturating_sub(1)));
             }
-            y += height as usize;
+            KeyCode::Down => {
+                let Some(last) = last else { return false };
+                state.selected = Some(state.selected.map_or(0, |i| (i + 1).min(last)));
+            }
+            KeyCode::PageUp => state.scroll_by(-page),
+            KeyCode::PageDown => state.scroll_by(page),
+            KeyCode::Home => state.scroll_to(0),
+            KeyCode::End => state.scroll_to(u16::MAX),
+            _ => return false,
         }
 
-        y
+        true
     }
 
-    fn render_scrollbar(
+    /// Index of the child shown at column `x` and row `y` when the panel is rendered into `area`
+    /// with `state`, or `None` if there isn't one there.
+    pub fn child_at(&self, state: &PanelState, area: Rect, x: u16, y: u16) -> Option<usize> {
+        let (inner, _) = self.children_area(area);
+        let inner = self.aligned_children_area(inner);
+        if !inner.contains(Position::new(x, y)) {
+            return None;
+        }
+
+        let column = (x - inner.x) as usize + state.scroll_offset_x as usize;
+        let row = (y - inner.y) as usize + state.scroll_offset as usize;
+        self.layout(inner.width).iter().position(|extent| {
+            (extent.x..extent.x + extent.width as usize).contains(&column)
+                && (extent.y..extent.y + extent.height).contains(&row)
+        })
+    }
+
+    /// Selects the child that's left clicked on when the panel is rendered into `area`, returning
+    /// whether a child was clicked.
+    pub fn handle_mouse_event(
         &self,
+        state: &mut PanelState,
         area: Rect,
-        buf: &mut Buffer,
-        children_height: usize,
-        display_height: usize,
-    ) {
-        if self.scrollbar {
-            let scrollbar_area = Rect::new(
-                area.left(),
-                area.top() + 1,
-                area.right() - area.left(),
-                area.bottom() - area.top() - 2,
-            );
-            let scrollbar_height = children_height.saturating_sub(display_height);
-            let scrollbar = Scrollbar::new(ratatui::widgets::ScrollbarOrientation::VerticalRight)
-                .begin_symbol(Some("↑"))
-                .end_symbol(Some("↓"));
-            let mut scrollbar_state = ScrollbarState::new(scrollbar_height).position(0);
-            scrollbar.render(scrollbar_area, buf, &mut scrollbar_state);
+        event: MouseEvent,
+    ) -> bool {
+        if event.kind != MouseEventKind::Down(MouseButton::Left) {
+            return false;
         }
+
+        let Some(index) = self.child_at(state, area, event.column, event.row) else {
+            return false;
+        };
+        state.selected = Some(index);
+        true
     }
-}
 
-impl WidgetRef for Panel<'_> {
-    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
-        let inner = self.render_outer(area, buf);
+    /// The width and height the panel takes up when given at most `max_width` columns, including
+    /// any border and padding. Useful for sizing a panel to fit what's in it.
+    pub fn measure(&self, max_width: u16) -> (u16, u16) {
+        let area = Rect {
+            x: 0,
+            y: 0,
+            width: max_width,
+            height: u16::MAX,
+        };
+        let inner = self.full_size_inner(area);
+        let children_width = match self.direction {
+            PanelDirection::Vertical => self
+                .children
+                .iter()
+                .map(|child| child.measure(inner.width).0)
+                .max()
+                .unwrap_or(0),
+            PanelDirection::Horizontal => u16::try_from(self.children_width(inner.width))
+                .unwrap_or(u16::MAX)
+                .min(inner.width),
+        };
+        let width = children_width
+            .saturating_add(area.width - inner.width)
+            .min(max_width);
 
-        let children_height = self.render_children(inner, buf);
+        (width, self.get_height(max_width))
+    }
 
-        self.render_scrollbar(
-            area,
-            buf,
-            children_height,
-            (inner.bottom() - inner.top()) as usize,
-        );
+    /// The area inside the borders and padding when the panel is rendered into `area`, this is
+    /// all of `area` for a panel without a block. Any space for the scrollbar is included.
+    pub fn inner_area(&self, area: Rect) -> Rect {
+        self.block
+            .as_ref()
+            .map_or(area, |block| block.block(false).inner(area))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Height needed to show all the children plus any border and padding at the given width.
+    pub(crate) fn get_height(&self, width: u16) -> u16 {
+        // Built directly as `Rect::new` would shrink this to keep the area within a u16.
+        let area = Rect {
+            x: 0,
+            y: 0,
+            width,
+            height: u16::MAX,
+        };
+        let inner = self.full_size_inner(area);
+        let insets = (area.height - inner.height) as usize;
 
-    use tracing_test::traced_test;
+        u16::try_from(self.children_height(inner.width) + insets).unwrap_or(u16::MAX)
+    }
 
-    #[traced_test]
-    #[test]
-    fn test_simple_text_lines() {
-        let panel = Panel::new_builder(Some("Panel Test".to_string()))
-            .padding(Padding::symmetric(0, 0))
-            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
-            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
-            .add_child(PanelWidget::Text(Text::from("Hello 3!")))
-            .build();
+    /// Width needed to show all the children side by side, or the widest of them when stacked, plus
+    /// any border and padding.
+    pub(crate) fn get_width(&self) -> u16 {
+        let widths = self.children.iter().map(|child| child.get_width());
+        let children_width = match self.direction {
+            PanelDirection::Vertical => widths.max().unwrap_or(0),
+            PanelDirection::Horizontal => {
+                let gaps = self.children.len().saturating_sub(1);
+                let spacing =
+                    u16::try_from(gaps).map_or(u16::MAX, |gaps| gaps.saturating_mul(self.spacing));
+                widths.fold(spacing, u16::saturating_add)
+            }
+        };
 
-        let rect = Rect::new(0, 0, 40, 12);
-        let mut buffer = Buffer::empty(rect);
+        let area = Rect {
+            x: 0,
+            y: 0,
+            width: u16::MAX,
+            height: u16::MAX,
+        };
+        let insets = area.width - self.full_size_inner(area).width;
 
-        panel.render_ref(rect, &mut buffer);
+        children_width.saturating_add(insets)
+    }
 
-        insta::assert_debug_snapshot!(buffer);
+    /// Area left for the children within `area` when they can all be shown, so there's only a
+    /// scrollbar if it's always on.
+    fn full_size_inner(&self, area: Rect) -> Rect {
+        let inner = self.inner_area(area);
+        let scrollbar = self.scrollbar && !self.scrollbar_auto;
+        reserve_scrollbar_space(area, inner, scrollbar, &self.scrollbar_orientation)
     }
 
-    #[traced_test]
-    #[test]
-    fn test_simple_long_text_lines() {
-        let panel = Panel::new_builder(Some("Panel Test".to_string()))
-            .add_child(PanelWidget::Text(Text::from("Let's make several strings that are longer than the 40 characters of the rectangle.")))
-            .add_child(PanelWidget::Text(Text::from("Let's make several strings that are longer than the 40 characters of the rectangle.")))
-            .add_child(PanelWidget::Text(Text::from("Let's make several strings that are longer than the 40 characters of the rectangle.")))
-            .build();
+    fn is_vertical_scrollbar(&self) -> bool {
+        matches!(
+            self.scrollbar_orientation,
+            ScrollbarOrientation::VerticalLeft | ScrollbarOrientation::VerticalRight
+        )
+    }
 
-        let rect = Rect::new(0, 0, 40, 12);
-        let mut buffer = Buffer::empty(rect);
+    /// Whether there's a scrollbar given whether the children overflow the space for them along
+    /// the direction the scrollbar scrolls, which is only found out if it matters.
+    fn shows_scrollbar(&self, overflows: impl FnOnce() -> bool) -> bool {
+        self.scrollbar && (!self.scrollbar_auto || overflows())
+    }
 
-        panel.render_ref(rect, &mut buffer);
+    fn render_outer(&self, area: Rect, buf: &mut Buffer, focused: bool) {
+        if let Some(block) = self.block.as_ref() {
+            block.block(focused).render_ref(area, buf);
+        }
+        // The background fills everything inside the border, padding included.
+        buf.set_style(self.inside_border(area), self.style);
+    }
+
+    /// The area inside the borders and title but not the padding.
+    fn inside_border(&self, area: Rect) -> Rect {
+        self.block.as_ref().map_or(area, |block| {
+            block.block(false).padding(Padding::ZERO).inner(area)
+        })
+    }
+
+    /// Area the children are shown in when the panel is rendered into `area`, and whether
+    /// there's a scrollbar.
+    fn children_area(&self, area: Rect) -> (Rect, bool) {
+        let full_inner = self.inner_area(area);
+        let scrollbar = if self.is_vertical_scrollbar() {
+            self.shows_scrollbar(|| {
+                self.children_taller_than(full_inner.width, full_inner.height as usize)
+            })
+        } else {
+            self.shows_scrollbar(|| {
+                self.children_width(full_inner.width) > full_inner.width as usize
+            })
+        };
+        // Making room for the scrollbar only makes the children longer, so they still overflow.
+        let inner =
+            reserve_scrollbar_space(area, full_inner, scrollbar, &self.scrollbar_orientation);
+        (inner, scrollbar)
+    }
+
+    /// Where each child goes within the combined children laid out at `width` columns, in the same
+    /// order as the children.
+    fn layout(&self, width: u16) -> Rc<[ChildExtent]> {
+        if let Some((_, extents)) = self.layout_cache.borrow().iter().find(|(w, _)| *w == width) {
+            return Rc::clone(extents);
+        }
+
+        let extents: Rc<[ChildExtent]> = self.measure_children(width).into();
+        let mut cache = self.layout_cache.borrow_mut();
+        if cache.len() == LAYOUT_CACHE_SIZE {
+            cache.remove(0);
+        }
+        cache.push((width, Rc::clone(&extents)));
+        extents
+    }
+
+    fn measure_children(&self, width: u16) -> Vec<ChildExtent> {
+        let mut extents = Vec::with_capacity(self.children.len());
+        match self.direction {
+            PanelDirection::Vertical => {
+                let mut y = 0;
+                for (index, child) in self.children.iter().enumerate() {
+                    if index > 0 {
+                        y += self.spacing as usize;
+                    }
+                    let height = child.get_height(width) as usize;
+                    extents.push(ChildExtent {
+                        x: 0,
+                        y,
+                        width,
+                        height,
+                    });
+                    y += height;
+                }
+            }
+            PanelDirection::Horizontal => {
+                let mut x = 0;
+                for (index, child) in self.children.iter().enumerate() {
+                    if index > 0 {
+                        x += self.spacing as usize;
+                    }
+                    let child_width = child.get_width().min(width);
+                    extents.push(ChildExtent {
+                        x,
+                        y: 0,
+                        width: child_width,
+                        height: child.get_height(child_width) as usize,
+                    });
+                    x += child_width as usize;
+                }
+            }
+        }
+        extents
+    }
+
+    /// Whether the children laid out at `width` are taller than `height`. Stacked children that
+    /// aren't already laid out at that width are only measured until they fill the height, so
+    /// with lots of children this is limited by the height rather than how many there are.
+    fn children_taller_than(&self, width: u16, height: usize) -> bool {
+        let laid_out = self.layout_cache.borrow().iter().any(|(w, _)| *w == width);
+        if laid_out || self.direction == PanelDirection::Horizontal {
+            return self.children_height(width) > height;
+        }
+
+        let mut y = 0;
+        for (index, child) in self.children.iter().enumerate() {
+            if index > 0 {
+                y += self.spacing as usize;
+            }
+            y += child.get_height(width) as usize;
+            if y > height {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn children_height(&self, width: u16) -> usize {
+        self.layout(width)
+            .iter()
+            .map(|extent| extent.y + extent.height)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The part of the inside of the panel the children are rendered in, this is all of it unless
+    /// they're aligned away from the top leaving space above them.
+    fn aligned_children_area(&self, inner: Rect) -> Rect {
+        let spare = (inner.height as usize).saturating_sub(self.children_height(inner.width));
+        let top = match self.vertical_alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Center => spare / 2,
+            VerticalAlignment::Bottom => spare,
+        } as u16;
+        Rect {
+            y: inner.y + top,
+            height: inner.height - top,
+            ..inner
+        }
+    }
+
+    fn children_width(&self, width: u16) -> usize {
+        self.layout(width)
+            .iter()
+            .map(|extent| extent.x + extent.width as usize)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Adjusts the scroll offset so the selected child is in view, showing its top when it is
+    /// taller than the display.
+    fn scroll_to_selected(&self, state: &mut PanelState, width: u16, display_height: usize) {
+        let Some(ChildExtent { y, height, .. }) = state
+            .selected
+            .and_then(|index| self.layout(width).get(index).copied())
+        else {
+            return;
+        };
+
+        if state.rendered_selected == state.selected {
+            return;
+        }
+
+        let offset = state.scroll_offset as usize;
+        if let Some(offset) = offset_showing(y, height, offset, display_height) {
+            state.scroll_to(offset);
+        }
+    }
+
+    /// Renders the laid out children scrolled by the offsets in `state`, highlighting the selected
+    /// one.
+    fn render_children(&self, area: Rect, buf: &mut Buffer, state: &PanelState) {
+        let width = area.right() - area.left();
+        let (top, left) = (state.scroll_offset as usize, state.scroll_offset_x as usize);
+        let (bottom, right) = (top + area.height as usize, left + width as usize);
+        tracing::trace!("area {:?}, offset {} {}", area, top, left);
+
+        // The children are laid out one after another, so the first one in view can be found
+        // without going through those before it and there's no need to go past the last.
+        let extents = self.layout(width);
+        let visible = match self.direction {
+            PanelDirection::Vertical => top..bottom,
+            PanelDirection::Horizontal => left..right,
+        };
+        let first =
+            extents.partition_point(|extent| extent.along(self.direction).end <= visible.start);
+
+        for (index, extent) in extents.iter().enumerate().skip(first) {
+            if extent.along(self.direction).start >= visible.end {
+                break;
+            }
+            let child = &self.children[index];
+            let (start, end) = (extent.y.max(top), (extent.y + extent.height).min(bottom));
+            let start_x = extent.x.max(left);
+            let end_x = (extent.x + extent.width as usize).min(right);
+
+            if start < end && start_x < end_x {
+                let child_area = Rect::new(
+                    area.left() + (start_x - left) as u16,
+                    area.top() + (start - top) as u16,
+                    (end_x - start_x) as u16,
+                    (end - start) as u16,
+                );
+                tracing::trace!("Rendering child in area {:?}", child_area);
+                let skip = ((start_x - extent.x) as u16, (start - extent.y) as u16);
+                render_clipped(child, child_area, extent.width, skip, buf);
+                if state.selected == Some(index) {
+                    buf.set_style(child_area, self.highlight_style);
+                }
+            }
+        }
+    }
+
+    /// Renders the panel as it would be rendered with `state` into an area as tall as all of it,
+    /// with the first `skip` rows cut off. Everything but the children looks the same on each row
+    /// between the top and bottom of the panel, so that's rendered at a small height with its
+    /// middle row repeated, and only the children that are shown are rendered.
+    pub(crate) fn render_rows(&self, area: Rect, skip: u16, buf: &mut Buffer, state: &PanelState) {
+        let full = Rect {
+            x: 0,
+            y: 0,
+            width: area.width,
+            height: self.get_height(area.width),
+        };
+        let (inner, scrollbar) = self.children_area(full);
+        let (head, tail) = (inner.y + 1, full.bottom() - inner.bottom() + 1);
+
+        let small = Rect {
+            height: (head + tail + 1).min(full.height),
+            ..full
+        };
+        let mut scratch = Buffer::empty(small);
+        self.render_outer(small, &mut scratch, state.focused);
+        if scrollbar && self.is_vertical_scrollbar() {
+            // All the children are shown, so the thumb fills the track.
+            self.render_scrollbar(small, &mut scratch, 1, 1, 0);
+        } else if scrollbar {
+            let children_width = self.children_width(inner.width);
+            let display_width = inner.width as usize;
+            self.render_scrollbar(
+                small,
+                &mut scratch,
+                children_width,
+                display_width,
+                state.scroll_offset_x,
+            );
+        }
+        for row in 0..area.height {
+            let y = skip
+                .saturating_add(row)
+                .min(full.bottom().saturating_sub(1));
+            let from = if y < head {
+                y
+            } else if y >= full.bottom().saturating_sub(tail) {
+                y - (full.height - small.height)
+            } else {
+                head
+            };
+            for col in 0..area.width {
+                buf[(area.x + col, area.y + row)] = scratch[(col, from)].clone();
+            }
+        }
+
+        let (start, end) = (skip.max(inner.top()), skip.saturating_add(area.height));
+        let end = end.min(inner.bottom());
+        if start < end {
+            let children_area = Rect {
+                x: area.x + inner.x,
+                y: area.y + (start - skip),
+                width: inner.width,
+                height: end - start,
+            };
+            let state = PanelState {
+                scroll_offset: start - inner.top(),
+                scroll_offset_x: state.scroll_offset_x,
+                selected: state.selected,
+                ..PanelState::default()
+            };
+            self.render_children(children_area, buf, &state);
+        }
+    }
+
+    fn render_scrollbar(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        content_length: usize,
+        display_length: usize,
+        offset: u16,
+    ) {
+        // The scrollbar is drawn along the edge of the area, but only between any borders (or
+        // title) across the ends of it.
+        let inside_border = self.inside_border(area);
+        let (scrollbar_area, begin, end) = if self.is_vertical_scrollbar() {
+            (
+                Rect {
+                    y: inside_border.y,
+                    height: inside_border.height,
+                    ..area
+                },
+                "↑",
+                "↓",
+            )
+        } else {
+            (
+                Rect {
+                    x: inside_border.x,
+                    width: inside_border.width,
+                    ..area
+                },
+                "←",
+                "→",
+            )
+        };
+        // Ratatui takes the content length as the number of positions that can be scrolled to, so
+        // with the viewport added on the thumb is the size of the display against the content.
+        let positions = content_length.saturating_sub(display_length) + 1;
+        let scrollbar = Scrollbar::new(self.scrollbar_orientation.clone())
+            .begin_symbol(Some(begin))
+            .end_symbol(Some(end));
+        let mut scrollbar_state = ScrollbarState::new(positions)
+            .viewport_content_length(display_length)
+            .position(offset as usize);
+        scrollbar.render(scrollbar_area, buf, &mut scrollbar_state);
+    }
+}
+
+/// Scrolls a panel rendered without a [`PanelState`], Up and Down scroll a row at a time and Home
+/// goes back to the top. Keys are only used if they scroll, so at either end they're left for
+/// the caller.
+impl Handle for Panel<'_> {
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        let offset = self.scroll_offset;
+        match key.code {
+            KeyCode::Up => self.scroll_by(-1),
+            KeyCode::Down => self.scroll_by(1),
+            KeyCode::Home => self.scroll_to(0),
+            _ => return false,
+        }
+
+        self.scroll_offset != offset
+    }
+}
+
+/// The scroll offset that brings the rows `y..y + height` into a display of `display_height`
+/// rows currently scrolled to `offset`, or `None` if they're already in view.
+fn offset_showing(y: usize, height: usize, offset: usize, display_height: usize) -> Option<u16> {
+    let offset = if y < offset || height > display_height {
+        y
+    } else if y + height > offset + display_height {
+        y + height - display_height
+    } else {
+        return None;
+    };
+
+    Some(u16::try_from(offset).unwrap_or(u16::MAX))
+}
+
+impl WidgetRef for Panel<'_> {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let mut state = PanelState {
+            scroll_offset: self.scroll_offset,
+            ..PanelState::default()
+        };
+        StatefulWidget::render(self, area, buf, &mut state);
+        self.max_scroll_offset.set(state.max_scroll_offset);
+    }
+}
+
+impl StatefulWidget for &Panel<'_> {
+    type State = PanelState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        self.render_outer(area, buf, state.focused);
+        let (inner, scrollbar) = self.children_area(area);
+        let display_height = (inner.bottom() - inner.top()) as usize;
+        let display_width = inner.width as usize;
+        let children_height = self.children_height(inner.width);
+        let children_width = self.children_width(inner.width);
+
+        let max_scroll_offset = children_height.saturating_sub(display_height);
+        state.max_scroll_offset = Some(u16::try_from(max_scroll_offset).unwrap_or(u16::MAX));
+        state.scroll_to(state.scroll_offset);
+        let max_scroll_offset_x = children_width.saturating_sub(display_width);
+        state.max_scroll_offset_x = Some(u16::try_from(max_scroll_offset_x).unwrap_or(u16::MAX));
+        state.scroll_x_to(state.scroll_offset_x);
+        // Children may have been removed since the selection was made.
+        state.selected = state
+            .selected
+            .and_then(|index| Some(index.min(self.children.len().checked_sub(1)?)));
+        self.scroll_to_selected(state, inner.width, display_height);
+        state.display_height = display_height as u16;
+        state.rendered_selected = state.selected;
+
+        self.render_children(self.aligned_children_area(inner), buf, state);
+
+        if scrollbar && self.is_vertical_scrollbar() {
+            self.render_scrollbar(
+                area,
+                buf,
+                children_height,
+                display_height,
+                state.scroll_offset,
+            );
+        } else if scrollbar {
+            self.render_scrollbar(
+                area,
+                buf,
+                children_width,
+                display_width,
+                state.scroll_offset_x,
+            );
+        }
+    }
+}
+
+impl StatefulWidget for Panel<'_> {
+    type State = PanelState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(&self, area, buf, state);
+    }
+}
+
+/// Takes a row or column off the side of `inner` the scrollbar is on if the scrollbar, drawn along
+/// that edge of `area`, would otherwise cover the children rather than a border.
+fn reserve_scrollbar_space(
+    area: Rect,
+    inner: Rect,
+    scrollbar: bool,
+    orientation: &ScrollbarOrientation,
+) -> Rect {
+    if !scrollbar || inner.is_empty() {
+        return inner;
+    }
+
+    match orientation {
+        ScrollbarOrientation::VerticalRight if inner.right() >= area.right() => Rect {
+            width: inner.width - 1,
+            ..inner
+        },
+        ScrollbarOrientation::VerticalLeft if inner.left() <= area.left() => Rect {
+            x: inner.x + 1,
+            width: inner.width - 1,
+            ..inner
+        },
+        ScrollbarOrientation::HorizontalBottom if inner.bottom() >= area.bottom() => Rect {
+            height: inner.height - 1,
+            ..inner
+        },
+        ScrollbarOrientation::HorizontalTop if inner.top() <= area.top() => Rect {
+            y: inner.y + 1,
+            height: inner.height - 1,
+            ..inner
+        },
+        _ => inner,
+    }
+}
+
+/// Renders `widget`, laid out `width` columns wide, into `area` with its first `skip` columns and
+/// rows cut off. Anything cut off other than at the bottom goes through a scratch buffer, as
+/// widgets always draw from the top left of their area and may lay themselves out differently when
+/// narrower.
+fn render_clipped(
+    widget: &PanelWidget<'_>,
+    area: Rect,
+    width: u16,
+    skip: (u16, u16),
+    buf: &mut Buffer,
+) {
+    let (skip_x, skip_y) = skip;
+    if skip_x == 0 && skip_y == 0 && area.width == width {
+        widget.render_ref(area, buf);
+        return;
+    }
+
+    let scratch_area = Rect::new(0, 0, width, area.height);
+    let mut scratch = Buffer::empty(scratch_area);
+    widget.render_rows(scratch_area, skip_y, &mut scratch);
+    for row in 0..area.height {
+        for col in 0..area.width {
+            buf[(area.x + col, area.y + row)] = scratch[(skip_x + col, row)].clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+
+    use ratatui::buffer::Cell as BufferCell;
+
+    use ratatui::style::{Color, Modifier};
+    use tracing_test::traced_test;
+
+    #[traced_test]
+    #[test]
+    fn test_simple_text_lines() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 3!")))
+            .build();
+
+        let rect = Rect::new(0, 0, 40, 12);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_simple_long_text_lines() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .add_child(PanelWidget::Text(Text::from("Let's make several strings that are longer than the 40 characters of the rectangle.")))
+            .add_child(PanelWidget::Text(Text::from("Let's make several strings that are longer than the 40 characters of the rectangle.")))
+            .add_child(PanelWidget::Text(Text::from("Let's make several strings that are longer than the 40 characters of the rectangle.")))
+            .build();
+
+        let rect = Rect::new(0, 0, 40, 12);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrolled_to_bottom() {
+        let mut builder = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .scroll_offset(5);
+        for _ in 0..5 {
+            builder = builder.add_child(PanelWidget::Text(Text::from("Let's make several strings that are longer than the 40 characters of the rectangle.")));
+        }
+        let panel = builder.build();
+
+        let rect = Rect::new(0, 0, 40, 12);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    /// A titled panel without padding whose children are each a line saying hello.
+    fn hello_panel(children: usize) -> PanelBuilder<'static> {
+        let mut builder =
+            Panel::new_builder(Some("Panel Test".to_string())).padding(Padding::symmetric(0, 0));
+        for i in 1..=children {
+            builder = builder.add_child(PanelWidget::Text(Text::from(
+                format!("Hello {i}!").as_str(),
+            )));
+        }
+        builder
+    }
+
+    fn long_text_panel(children: usize) -> Panel<'static> {
+        let mut builder =
+            Panel::new_builder(Some("Panel Test".to_string())).padding(Padding::symmetric(0, 0));
+        for _ in 0..children {
+            builder = builder.add_child(PanelWidget::Text(Text::from("Let's make several strings that are longer than the 40 characters of the rectangle.")));
+        }
+        builder.build()
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_state_scroll_clamped_to_content() {
+        // 5 children of 3 lines each in a panel showing 10 lines.
+        let panel = long_text_panel(5);
+        let rect = Rect::new(0, 0, 40, 12);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default();
+
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        state.scroll_by(3);
+        assert_eq!(state.scroll_offset(), 3);
+        state.scroll_by(100);
+        assert_eq!(state.scroll_offset(), 5);
+        state.scroll_by(-2);
+        assert_eq!(state.scroll_offset(), 3);
+        state.scroll_by(-100);
+        assert_eq!(state.scroll_offset(), 0);
+        state.scroll_to(u16::MAX);
+        assert_eq!(state.scroll_offset(), 5);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_state_offset_clamped_on_render() {
+        let panel = long_text_panel(5);
+        let rect = Rect::new(0, 0, 40, 12);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default().with_scroll_offset(20);
+
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        assert_eq!(state.scroll_offset(), 5);
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_state_no_scroll_when_content_fits() {
+        let panel = long_text_panel(2);
+        let rect = Rect::new(0, 0, 40, 12);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default();
+
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+        state.scroll_by(1);
+
+        assert_eq!(state.scroll_offset(), 0);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrollbar_thumb_follows_offset() {
+        let panel = long_text_panel(5);
+        let rect = Rect::new(0, 0, 40, 12);
+        let thumb_rows = |buffer: &Buffer| {
+            (2..10)
+                .filter(|&y| buffer[(39, y)].symbol() == "█")
+                .collect::<Vec<_>>()
+        };
+
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default();
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+        // Two thirds of the content is shown so the thumb is two thirds of the 8 rows of track.
+        assert_eq!(thumb_rows(&buffer), vec![2, 3, 4, 5, 6]);
+
+        let mut buffer = Buffer::empty(rect);
+        state.scroll_to(5);
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+        assert_eq!(thumb_rows(&buffer), vec![5, 6, 7, 8, 9]);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_nested_panels() {
+        let top = Panel::new_builder(Some("Top".to_string()))
+            .padding(Padding::symmetric(1, 0))
+            .add_child(PanelWidget::Text(Text::from("Hello from the top panel!")))
+            .build();
+        let bottom = Panel::new_builder(Some("Bottom".to_string()))
+            .padding(Padding::symmetric(1, 0))
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .build();
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .add_child(PanelWidget::Panel(top))
+            .add_child(PanelWidget::Panel(bottom))
+            .build();
+
+        let rect = Rect::new(0, 0, 30, 12);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    /// Draws a box of `#` characters of a fixed height.
+    #[derive(Debug)]
+    struct Boxed(u16);
+
+    impl WidgetRef for Boxed {
+        fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+            for position in area.positions() {
+                buf[position].set_char('#');
+            }
+        }
+    }
+
+    impl PanelChild for Boxed {
+        fn measured_height(&self, _width: u16) -> u16 {
+            self.0
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_custom_child() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Custom(Box::new(Boxed(3))))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .build();
+
+        let rect = Rect::new(0, 0, 20, 8);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_selected_child_highlighted() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 3!")))
+            .build();
+
+        let rect = Rect::new(0, 0, 20, 5);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default().with_selected(Some(1));
+
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_selection_scrolled_into_view() {
+        let panel = hello_panel(10).build();
+
+        let rect = Rect::new(0, 0, 20, 6);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default().with_selected(Some(9));
+
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+        assert_eq!(state.scroll_offset(), 6);
+        insta::assert_debug_snapshot!(buffer);
+
+        state.select(Some(2));
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+        assert_eq!(state.scroll_offset(), 2);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_tall_selection_shows_its_top() {
+        let panel = long_text_panel(3);
+        let rect = Rect::new(0, 0, 40, 4);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default().with_selected(Some(1));
+
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        assert_eq!(state.scroll_offset(), 3);
+    }
+
+    fn press(panel: &Panel<'_>, state: &mut PanelState, code: KeyCode) -> bool {
+        panel.handle_key_event(state, KeyEvent::from(code))
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_key_selection() {
+        let panel = long_text_panel(3);
+        let mut state = PanelState::default().with_focused(true);
+
+        assert!(press(&panel, &mut state, KeyCode::Down));
+        assert_eq!(state.selected(), Some(0));
+        press(&panel, &mut state, KeyCode::Down);
+        press(&panel, &mut state, KeyCode::Down);
+        press(&panel, &mut state, KeyCode::Down);
+        assert_eq!(state.selected(), Some(2));
+        press(&panel, &mut state, KeyCode::Up);
+        assert_eq!(state.selected(), Some(1));
+
+        state.select(None);
+        press(&panel, &mut state, KeyCode::Up);
+        assert_eq!(state.selected(), Some(2));
+
+        assert!(!press(&panel, &mut state, KeyCode::Char('x')));
+        assert!(!press(
+            &Panel::new_builder(None).build(),
+            &mut state,
+            KeyCode::Down
+        ));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_key_scrolling() {
+        // 5 children of 3 lines each in a panel showing 4 lines.
+        let panel = long_text_panel(5);
+        let rect = Rect::new(0, 0, 40, 6);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default().with_focused(true);
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        press(&panel, &mut state, KeyCode::PageDown);
+        assert_eq!(state.scroll_offset(), 4);
+        press(&panel, &mut state, KeyCode::End);
+        assert_eq!(state.scroll_offset(), 11);
+        press(&panel, &mut state, KeyCode::PageDown);
+        assert_eq!(state.scroll_offset(), 11);
+        press(&panel, &mut state, KeyCode::PageUp);
+        assert_eq!(state.scroll_offset(), 7);
+        press(&panel, &mut state, KeyCode::Home);
+        assert_eq!(state.scroll_offset(), 0);
+
+        let release = KeyEvent::new_with_kind(
+            KeyCode::End,
+            crossterm::event::KeyModifiers::NONE,
+            KeyEventKind::Release,
+        );
+        assert!(!panel.handle_key_event(&mut state, release));
+        assert_eq!(state.scroll_offset(), 0);
+
+        // Scrolling away from an unchanged selection isn't undone by the next render.
+        state.select(Some(0));
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+        press(&panel, &mut state, KeyCode::PageDown);
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+        assert_eq!(state.scroll_offset(), 4);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrollbar_hidden_when_content_fits_exactly() {
+        // 3 children of 3 lines each in a panel showing 9 lines.
+        let panel = long_text_panel(3);
+        let rect = Rect::new(0, 0, 40, 11);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrollbar_shown_when_content_overflows() {
+        let panel = long_text_panel(3);
+        let rect = Rect::new(0, 0, 40, 10);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        assert_eq!(buffer[(39, 1)].symbol(), "↑");
+        assert_eq!(buffer[(39, 8)].symbol(), "↓");
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrollbar_always_shown() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .scrollbar_auto(false)
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .build();
+        let rect = Rect::new(0, 0, 20, 6);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        assert_eq!(buffer[(19, 1)].symbol(), "↑");
+        assert_eq!(buffer[(19, 4)].symbol(), "↓");
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_children_narrowed_for_scrollbar() {
+        let mut builder = Panel::new_builder(None);
+        for _ in 0..2 {
+            builder = builder.add_child(PanelWidget::Text(Text::from("Let's make several strings that are longer than the 20 characters of the rectangle.")));
+        }
+        let panel = builder.build();
+
+        let rect = Rect::new(0, 0, 20, 8);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_horizontal_children() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .direction(Direction::Horizontal)
+            .add_child(PanelWidget::Text(Text::from("One  ")))
+            .add_child(PanelWidget::Text(Text::from("Two\nlines  ")))
+            .add_child(PanelWidget::Text(Text::from("Three")))
+            .build();
+
+        let rect = Rect::new(0, 0, 24, 5);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_spacing_between_children() {
+        let panel = Panel::new_builder(None)
+            .spacing(1)
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 3!")))
+            .build();
+        assert_eq!(panel.get_height(20), 5);
+
+        let rect = Rect::new(0, 0, 10, 6);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        let rows = (0..6)
+            .map(|y| (0..10).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                "Hello 1!  ",
+                "          ",
+                "Hello 2!  ",
+                "          ",
+                "Hello 3!  ",
+                "          ",
+            ]
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_background_style() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .style(Style::new().bg(Color::Blue))
+            .add_child(PanelWidget::Text(
+                Text::from("Hello 1!").with_style(Style::new().fg(Color::Yellow)),
+            ))
+            .build();
+
+        let rect = Rect::new(0, 0, 20, 4);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_centered_title() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .title_alignment(Alignment::Center)
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .build();
+
+        let rect = Rect::new(0, 0, 40, 5);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_styled_title() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .title_style(Style::new().add_modifier(Modifier::BOLD))
+            .title_alignment(Alignment::Right)
+            .build();
+
+        let rect = Rect::new(0, 0, 20, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        for x in 9..19 {
+            assert_eq!(buffer[(x, 0)].modifier, Modifier::BOLD);
+        }
+        assert_eq!(buffer[(8, 0)].modifier, Modifier::empty());
+        assert_eq!(buffer[(8, 0)].symbol(), "─");
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_rounded_cyan_border() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .border_type(BorderType::Rounded)
+            .border_style(Style::new().fg(Color::Cyan))
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .build();
+
+        let rect = Rect::new(0, 0, 20, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_many_children_in_short_panel() {
+        let mut builder = Panel::new_builder(Some("Panel Test".to_string()));
+        for i in 1..=10 {
+            builder = builder.add_child(PanelWidget::Text(Text::from(
+                format!("Hello {i}!").as_str(),
+            )));
+        }
+        let panel = builder.build();
+
+        let rect = Rect::new(0, 0, 40, 5);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+
+        for height in 0..5 {
+            let rect = Rect::new(0, 0, 40, height);
+            panel.render_ref(rect, &mut Buffer::empty(rect));
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_children_height_includes_hidden_children() {
+        let panel = long_text_panel(5);
+        let rect = Rect::new(0, 0, 40, 12);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default();
+
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        let display_height = state.display_height as usize;
+        let children_height = panel.children_height(38);
+        assert_eq!(display_height, 10);
+        assert_eq!(children_height, 15);
+        assert!(children_height > display_height);
+        assert_eq!(state.max_scroll_offset, Some(5));
+    }
+
+    fn child_text(panel: &Panel<'_>, index: usize) -> String {
+        match &panel.children[index] {
+            PanelWidget::Text(text) => text.wrapped_lines(40).collect(),
+            child => panic!("Expected a text child, got {child:?}"),
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_insert_and_remove_children() {
+        let mut panel = Panel::new_builder(None)
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 3!")))
+            .build();
+
+        assert!(panel.remove_child(0).is_some());
+        assert!(panel.remove_child(5).is_none());
+        assert_eq!(panel.children.len(), 2);
+        assert_eq!(child_text(&panel, 0), "Hello 2!");
+        assert_eq!(child_text(&panel, 1), "Hello 3!");
+
+        panel.insert_child(1, PanelWidget::Text(Text::from("Hello 4!")));
+        panel.insert_child(10, PanelWidget::Text(Text::from("Hello 5!")));
+        assert_eq!(child_text(&panel, 1), "Hello 4!");
+        assert_eq!(child_text(&panel, 2), "Hello 3!");
+        assert_eq!(child_text(&panel, 3), "Hello 5!");
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_selection_kept_valid_after_removal() {
+        let mut panel = Panel::new_builder(None)
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 3!")))
+            .build();
+        let rect = Rect::new(0, 0, 20, 5);
+        let mut state = PanelState::default().with_selected(Some(2));
+
+        panel.remove_child(2);
+        StatefulWidget::render(&panel, rect, &mut Buffer::empty(rect), &mut state);
+        assert_eq!(state.selected(), Some(1));
+
+        panel.clear_children();
+        assert!(panel.children.is_empty());
+        StatefulWidget::render(&panel, rect, &mut Buffer::empty(rect), &mut state);
+        assert_eq!(state.selected(), None);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_children_accessors() {
+        let panel = Panel::new_builder(None)
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Panel(Panel::new_builder(None).build()))
+            .build();
+
+        assert_eq!(panel.child_count(), 2);
+        assert_eq!(panel.children().len(), 2);
+        assert!(matches!(panel.child(0), Some(PanelWidget::Text(_))));
+        assert!(matches!(panel.child(1), Some(PanelWidget::Panel(_))));
+        assert!(panel.child(2).is_none());
+        assert_eq!(child_text(&panel, 0), "Hello 1!");
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_horizontal_scrollbar() {
+        let mut builder = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .direction(Direction::Horizontal)
+            .scrollbar_orientation(ScrollbarOrientation::HorizontalBottom)
+            .spacing(1);
+        for i in 1..=4 {
+            builder = builder.add_child(PanelWidget::Text(Text::from(
+                format!("Column {i}\nrow 2").as_str(),
+            )));
+        }
+        let panel = builder.build();
+
+        let rect = Rect::new(0, 0, 24, 5);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default().with_scroll_offset_x(12);
+
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        assert_eq!(state.scroll_offset_x(), 12);
+        insta::assert_debug_snapshot!(buffer);
+
+        state.scroll_x_by(100);
+        assert_eq!(state.scroll_offset_x(), 13);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_theme_highlight_style() {
+        let theme = Theme {
+            highlight_style: Style::new().bg(Color::Magenta),
+            ..Theme::default()
+        };
+        let panel = Panel::new_builder(None)
+            .theme(theme)
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .build();
+
+        let rect = Rect::new(0, 0, 10, 2);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default().with_selected(Some(1));
+
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        assert_eq!(buffer[(0, 0)].bg, Color::Reset);
+        assert_eq!(buffer[(0, 1)].bg, Color::Magenta);
+        assert_eq!(buffer[(0, 1)].modifier, Modifier::empty());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_explicit_style_overrides_theme() {
+        let theme = Theme {
+            highlight_style: Style::new().bg(Color::Magenta),
+            ..Theme::default()
+        };
+        let panel = Panel::new_builder(None)
+            .theme(theme)
+            .highlight_style(Style::new().bg(Color::Green))
+            .build();
+
+        assert_eq!(panel.highlight_style, Style::new().bg(Color::Green));
+    }
+
+    #[cfg(feature = "serde")]
+    #[traced_test]
+    #[test]
+    fn test_serialize_layout() {
+        let builder = Panel::new_builder(Some("Layout".to_string()))
+            .borders(Borders::TOP | Borders::BOTTOM)
+            .padding_uniform(1)
+            .direction(Direction::Horizontal)
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Divider(Divider::new()))
+            .add_child(PanelWidget::Panel(
+                Panel::new_builder(None)
+                    .style(Style::new().bg(Color::Blue))
+                    .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+                    .build(),
+            ));
+
+        insta::assert_json_snapshot!(builder);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_measure() {
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Text(Text::from("A longer line of text")))
+            .build();
+
+        // The border and padding take up 6 columns and 4 rows.
+        assert_eq!(panel.measure(40), (27, 6));
+        assert_eq!(panel.measure(16), (14, 8));
+
+        let panel = Panel::new_builder(None)
+            .direction(Direction::Horizontal)
+            .spacing(1)
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .build();
+
+        assert_eq!(panel.measure(40), (17, 1));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_focused_border() {
+        let panel = |title: &str| {
+            Panel::new_builder(Some(title.to_string()))
+                .padding(Padding::symmetric(0, 0))
+                .focus_style(Style::new().fg(Color::Yellow))
+                .add_child(PanelWidget::Text(Text::from("Hello!")))
+                .build()
+        };
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 24, 3));
+        let mut focused = PanelState::default().with_focused(true);
+        let mut unfocused = PanelState::default();
+
+        StatefulWidget::render(
+            &panel("Focused"),
+            Rect::new(0, 0, 12, 3),
+            &mut buffer,
+            &mut focused,
+        );
+        StatefulWidget::render(
+            &panel("Other"),
+            Rect::new(12, 0, 12, 3),
+            &mut buffer,
+            &mut unfocused,
+        );
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_unfocused_ignores_keys() {
+        let panel = long_text_panel(3);
+        let mut state = PanelState::default();
+
+        assert!(!press(&panel, &mut state, KeyCode::Down));
+        assert_eq!(state.selected(), None);
+
+        state.set_focused(true);
+        assert!(press(&panel, &mut state, KeyCode::Down));
+        assert_eq!(state.selected(), Some(0));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_child_at() {
+        // 5 children of 3 lines each in a panel showing 4 lines.
+        let panel = long_text_panel(5);
+        let rect = Rect::new(0, 0, 40, 6);
+        let mut state = PanelState::default();
+
+        assert_eq!(panel.child_at(&state, rect, 5, 1), Some(0));
+        assert_eq!(panel.child_at(&state, rect, 5, 3), Some(0));
+        assert_eq!(panel.child_at(&state, rect, 5, 4), Some(1));
+        // On the border.
+        assert_eq!(panel.child_at(&state, rect, 0, 1), None);
+        assert_eq!(panel.child_at(&state, rect, 5, 5), None);
+
+        state.scroll_offset = 4;
+        assert_eq!(panel.child_at(&state, rect, 5, 1), Some(1));
+        assert_eq!(panel.child_at(&state, rect, 5, 3), Some(2));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_child_at_horizontal() {
+        let panel = Panel::new_builder(None)
+            .direction(Direction::Horizontal)
+            .spacing(2)
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .build();
+        let rect = Rect::new(10, 10, 30, 2);
+        let state = PanelState::default();
+
+        assert_eq!(panel.child_at(&state, rect, 10, 10), Some(0));
+        assert_eq!(panel.child_at(&state, rect, 18, 10), None);
+        assert_eq!(panel.child_at(&state, rect, 20, 10), Some(1));
+        assert_eq!(panel.child_at(&state, rect, 20, 11), None);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_mouse_click_selects() {
+        let panel = long_text_panel(5);
+        let rect = Rect::new(0, 0, 40, 6);
+        let mut state = PanelState::default();
+        let click = |kind, row| MouseEvent {
+            kind,
+            column: 5,
+            row,
+            modifiers: crossterm::event::KeyModifiers::NONE,
+        };
+
+        assert!(panel.handle_mouse_event(
+            &mut state,
+            rect,
+            click(MouseEventKind::Down(MouseButton::Left), 4)
+        ));
+        assert_eq!(state.selected(), Some(1));
+        assert!(!panel.handle_mouse_event(
+            &mut state,
+            rect,
+            click(MouseEventKind::Down(MouseButton::Right), 1)
+        ));
+        assert!(!panel.handle_mouse_event(
+            &mut state,
+            rect,
+            click(MouseEventKind::Down(MouseButton::Left), 5)
+        ));
+        assert_eq!(state.selected(), Some(1));
+    }
+
+    /// Counts how many times it's measured and rendered.
+    #[derive(Debug)]
+    struct Counted {
+        measured: Rc<Cell<usize>>,
+        rendered: Rc<Cell<usize>>,
+    }
+
+    impl WidgetRef for Counted {
+        fn render_ref(&self, _area: Rect, _buf: &mut Buffer) {
+            self.rendered.set(self.rendered.get() + 1);
+        }
+    }
+
+    impl PanelChild for Counted {
+        fn measured_height(&self, _width: u16) -> u16 {
+            self.measured.set(self.measured.get() + 1);
+            1
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_large_panel_only_renders_visible() {
+        const CHILDREN: usize = 10_000;
+        let (measured, rendered) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let mut builder = Panel::new_builder(None);
+        for _ in 0..CHILDREN {
+            builder = builder.add_child(PanelWidget::Custom(Box::new(Counted {
+                measured: Rc::clone(&measured),
+                rendered: Rc::clone(&rendered),
+            })));
+        }
+        let panel = builder.build();
+        let rect = Rect::new(0, 0, 20, 10);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default();
+
+        let rows = rect.height as usize;
+
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        // Each child is measured once to lay them out beside the scrollbar, finding out there
+        // needs to be a scrollbar only takes measuring those that fill the display.
+        assert!(measured.get() <= CHILDREN + rows + 1);
+        assert_eq!(rendered.get(), rows);
+
+        let measured_before = measured.get();
+        state.scroll_to(5_000);
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        // The layout is kept, so after the first render only the display's worth is measured.
+        assert!(measured.get() - measured_before <= rows + 1);
+        assert_eq!(rendered.get(), 2 * rows);
+    }
+
+    fn numbered_lines(count: usize) -> Text {
+        Text::from_lines((1..=count).map(|i| format!("Line {i}")).collect())
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrolled_deep_into_tall_children() {
+        let nested = Panel::new_builder(Some("Nested".to_string()))
+            .padding(Padding::ZERO)
+            .add_child(PanelWidget::Text(numbered_lines(1000)))
+            .build();
+        let rect = Rect::new(0, 0, 80, 10);
+
+        for (child, expected) in [
+            (PanelWidget::Text(numbered_lines(1000)), "Line 901"),
+            (PanelWidget::Panel(nested), "│Line 900"),
+        ] {
+            let panel = Panel::new_builder(None)
+                .scrollbar(false)
+                .scroll_offset(900)
+                .add_child(child)
+                .build();
+            let mut buffer = Buffer::empty(rect);
+
+            panel.render_ref(rect, &mut buffer);
+
+            let row = (0..expected.chars().count() as u16)
+                .map(|x| buffer[(x, 0)].symbol())
+                .collect::<String>();
+            assert_eq!(row, expected);
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_render_rows_matches_full_render() {
+        let panel = Panel::new_builder(Some("Rows".to_string()))
+            .padding(Padding::symmetric(1, 1))
+            .scrollbar_auto(false)
+            .add_child(PanelWidget::Text(numbered_lines(20)))
+            .build();
+        let (width, height) = (20, panel.get_height(20));
+        let full_area = Rect::new(0, 0, width, height);
+        let mut full = Buffer::empty(full_area);
+        panel.render_ref(full_area, &mut full);
+
+        for skip in 0..height - 3 {
+            let area = Rect::new(0, 0, width, 4);
+            let mut rows = Buffer::empty(area);
+            panel.render_rows(area, skip, &mut rows, &PanelState::default());
+
+            for position in area.positions() {
+                let shown = Position::new(position.x, position.y + skip);
+                assert_eq!(rows[position], full[shown], "skip {skip} at {position:?}");
+            }
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_mixed_children() {
+        let group = Panel::new_builder(Some("Group".to_string()))
+            .padding(Padding::symmetric(1, 0))
+            .add_child(PanelWidget::Text(Text::from("Hello 2!")))
+            .add_child(PanelWidget::Text(Text::from("Hello 3!")))
+            .build();
+        let panel = Panel::new_builder(Some("Panel Test".to_string()))
+            .padding(Padding::symmetric(0, 0))
+            .add_child(PanelWidget::Text(Text::from("Hello 1!")))
+            .add_child(PanelWidget::Panel(group))
+            .add_child(PanelWidget::Divider(Divider::new()))
+            .add_child(PanelWidget::Custom(Box::new(Boxed(1))))
+            .build();
+
+        let rect = Rect::new(0, 0, 20, 10);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_panel_scroll_by() {
+        // 5 children of 3 lines each in a panel showing 4 lines.
+        let mut panel = long_text_panel(5);
+        let rect = Rect::new(0, 0, 40, 6);
+        let mut top = Buffer::empty(rect);
+        panel.render_ref(rect, &mut top);
+
+        panel.scroll_by(7);
+        panel.scroll_by(-1);
+        let mut scrolled = Buffer::empty(rect);
+        panel.render_ref(rect, &mut scrolled);
+
+        // Thumb moved down from the top of the track.
+        assert_eq!(top[(39, 2)].symbol(), "█");
+        assert_eq!(scrolled[(39, 2)].symbol(), "║");
+        insta::assert_debug_snapshot!(scrolled);
+
+        panel.scroll_to(u16::MAX);
+        let mut bottom = Buffer::empty(rect);
+        panel.render_ref(rect, &mut bottom);
+        panel.scroll_to(11);
+        let mut last = Buffer::empty(rect);
+        panel.render_ref(rect, &mut last);
+        assert_eq!(bottom, last);
+
+        panel.scroll_to(2);
+        panel.scroll_by(-5);
+        let mut unscrolled = Buffer::empty(rect);
+        panel.render_ref(rect, &mut unscrolled);
+        assert_eq!(unscrolled, top);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_children_stay_within_area() {
+        let mut builder = Panel::new_builder(None).scrollbar_auto(false);
+        for i in 0..20 {
+            builder = builder.add_child(PanelWidget::Text(Text::from(
+                format!("Hello {i}!").as_str(),
+            )));
+        }
+        let panel = builder.build();
+        let outer = Rect::new(0, 0, 12, 8);
+        let area = Rect::new(1, 2, 10, 3);
+
+        for offset in [0, 1, 10, 17, 18, u16::MAX] {
+            let mut buffer = Buffer::filled(outer, BufferCell::new("x"));
+            let mut state = PanelState::default().with_scroll_offset(offset);
+
+            StatefulWidget::render(&panel, area, &mut buffer, &mut state);
+
+            for position in outer.positions().filter(|&p| !area.contains(p)) {
+                assert_eq!(
+                    buffer[position].symbol(),
+                    "x",
+                    "drawn outside at {position:?}"
+                );
+            }
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_panel_direction() {
+        let panel = |direction: PanelDirection| {
+            Panel::new_builder(None)
+                .direction(direction)
+                .spacing(1)
+                .add_child(PanelWidget::Text(Text::from("One")))
+                .add_child(PanelWidget::Text(Text::from("Two")))
+                .add_child(PanelWidget::Text(Text::from("Three")))
+                .build()
+        };
+        let rect = Rect::new(0, 0, 16, 3);
+
+        assert_eq!(
+            PanelDirection::from(Direction::Horizontal),
+            PanelDirection::Horizontal
+        );
+        assert_eq!(
+            Direction::from(PanelDirection::Vertical),
+            Direction::Vertical
+        );
+
+        let mut buffer = Buffer::empty(rect);
+        panel(PanelDirection::Horizontal).render_ref(rect, &mut buffer);
+        assert_eq!(buffer, Buffer::with_lines(["One Two Three   ", "", ""]));
+
+        let mut buffer = Buffer::empty(rect);
+        panel(PanelDirection::Vertical).render_ref(rect, &mut buffer);
+        assert_eq!(
+            buffer,
+            Buffer::with_lines(["One            ↑", "               █", "Two            ↓"])
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrollbar_default() {
+        let render = |builder: PanelBuilder, lines: usize| {
+            let panel = builder
+                .add_child(PanelWidget::Text(Text::from(
+                    vec!["Line"; lines].join("\n").as_str(),
+                )))
+                .build();
+            let rect = Rect::new(0, 0, 8, 5);
+            let mut buffer = Buffer::empty(rect);
+            panel.render_ref(rect, &mut buffer);
+            buffer
+        };
+
+        for lines in [2, 8] {
+            assert_eq!(
+                render(PanelBuilder::default(), lines),
+                render(Panel::new_builder(None), lines)
+            );
+        }
+        insta::assert_debug_snapshot!(render(PanelBuilder::default(), 8));
+        insta::assert_debug_snapshot!(render(PanelBuilder::default().scrollbar(false), 8));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_vertical_alignment() {
+        for alignment in [
+            VerticalAlignment::Top,
+            VerticalAlignment::Center,
+            VerticalAlignment::Bottom,
+        ] {
+            let name = format!("vertical_alignment_{alignment:?}");
+            let panel = Panel::new_builder(Some("Aligned".to_string()))
+                .vertical_alignment(alignment)
+                .add_child(PanelWidget::Text(Text::from("One")))
+                .add_child(PanelWidget::Text(Text::from("Two")))
+                .add_child(PanelWidget::Text(Text::from("Three")))
+                .build();
+            let rect = Rect::new(0, 0, 14, 10);
+            let mut buffer = Buffer::empty(rect);
+
+            panel.render_ref(rect, &mut buffer);
+
+            insta::assert_debug_snapshot!(name, buffer);
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_handle_key_scrolls() {
+        // 10 children in a panel showing 4 of them, so it can scroll down 6 rows.
+        let mut panel = hello_panel(10).build();
+        let rect = Rect::new(0, 0, 20, 6);
+        panel.render_ref(rect, &mut Buffer::empty(rect));
+
+        assert!(panel.handle_key(KeyEvent::from(KeyCode::Down)));
+        assert!(panel.handle_key(KeyEvent::from(KeyCode::Down)));
+        assert_eq!(panel.scroll_offset, 2);
+        assert!(panel.handle_key(KeyEvent::from(KeyCode::Up)));
+        assert_eq!(panel.scroll_offset, 1);
+        assert!(panel.handle_key(KeyEvent::from(KeyCode::Home)));
+        assert_eq!(panel.scroll_offset, 0);
+
+        assert!(!panel.handle_key(KeyEvent::from(KeyCode::Char('x'))));
+        let release = KeyEvent::new_with_kind(
+            KeyCode::Down,
+            crossterm::event::KeyModifiers::NONE,
+            KeyEventKind::Release,
+        );
+        assert!(!panel.handle_key(release));
+        assert_eq!(panel.scroll_offset, 0);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_handle_key_at_scroll_boundary() {
+        let mut panel = hello_panel(10).build();
+        let rect = Rect::new(0, 0, 20, 6);
+        panel.render_ref(rect, &mut Buffer::empty(rect));
+
+        assert!(!panel.handle_key(KeyEvent::from(KeyCode::Up)));
+        assert!(!panel.handle_key(KeyEvent::from(KeyCode::Home)));
+        assert_eq!(panel.scroll_offset, 0);
+
+        panel.scroll_to(u16::MAX);
+        assert_eq!(panel.scroll_offset, 6);
+        assert!(!panel.handle_key(KeyEvent::from(KeyCode::Down)));
+        assert_eq!(panel.scroll_offset, 6);
+        assert!(panel.handle_key(KeyEvent::from(KeyCode::Up)));
+        assert_eq!(panel.scroll_offset, 5);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_widget_width() {
+        let text = PanelWidget::Text(Text::from("short\nthe longest line\nmedium line"));
+        assert_eq!(text.get_width(), 16);
+        assert_eq!(PanelWidget::Spacer(3).get_width(), 0);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_ensure_visible() {
+        let mut panel = hello_panel(10).scrollbar(false).build();
+        let rect = Rect::new(0, 0, 20, 6);
+
+        panel.ensure_visible(9, rect);
+        assert_eq!(panel.scroll_offset, 6);
+        panel.ensure_visible(7, rect);
+        assert_eq!(panel.scroll_offset, 6);
+        panel.ensure_visible(2, rect);
+        assert_eq!(panel.scroll_offset, 2);
+        panel.ensure_visible(6, rect);
+        assert_eq!(panel.scroll_offset, 3);
+        panel.ensure_visible(10, rect);
+        assert_eq!(panel.scroll_offset, 3);
+
+        let mut buffer = Buffer::empty(rect);
+        panel.render_ref(rect, &mut buffer);
+        assert_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "┌Panel Test────────┐",
+                "│Hello 4!          │",
+                "│Hello 5!          │",
+                "│Hello 6!          │",
+                "│Hello 7!          │",
+                "└──────────────────┘",
+            ])
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_ensure_visible_tall_child_shows_its_top() {
+        let mut panel = long_text_panel(3);
+        let rect = Rect::new(0, 0, 40, 4);
+
+        panel.ensure_visible(1, rect);
+
+        assert_eq!(panel.scroll_offset, 3);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scroll_clamped_to_content() {
+        let mut panel = hello_panel(10).scrollbar(false).build();
+        let rect = Rect::new(0, 0, 20, 6);
+        let mut buffer = Buffer::empty(rect);
+        panel.render_ref(rect, &mut buffer);
+
+        panel.scroll_to(1000);
+        assert_eq!(panel.scroll_offset, 6);
+        panel.scroll_by(-1);
+        assert_eq!(panel.scroll_offset, 5);
+
+        let mut buffer = Buffer::empty(rect);
+        panel.render_ref(rect, &mut buffer);
+        assert_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "┌Panel Test────────┐",
+                "│Hello 6!          │",
+                "│Hello 7!          │",
+                "│Hello 8!          │",
+                "│Hello 9!          │",
+                "└──────────────────┘",
+            ])
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_borders_without_title() {
+        let panel = Panel::new_builder(None)
+            .borders(Borders::ALL)
+            .add_child(PanelWidget::Text(Text::from("No title")))
+            .build();
+        let rect = Rect::new(0, 0, 14, 5);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_padding_without_title() {
+        let panel = Panel::new_builder(None)
+            .padding_uniform(1)
+            .add_child(PanelWidget::Text(Text::from("Padded")))
+            .build();
+        let rect = Rect::new(0, 0, 10, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        assert_eq!(buffer, Buffer::with_lines(["          ", " Padded   ", ""]));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_background_under_children() {
+        let panel = Panel::new_builder(Some("Panel".to_string()))
+            .style(Style::new().bg(Color::Blue))
+            .add_child(PanelWidget::Text(
+                Text::from("Yellow").with_style(Style::new().fg(Color::Yellow)),
+            ))
+            .add_child(PanelWidget::Text(
+                Text::from("Red").with_style(Style::new().bg(Color::Red)),
+            ))
+            .build();
+        let rect = Rect::new(0, 0, 12, 6);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        // The padding and children keep the background unless they set their own.
+        for position in Rect::new(1, 1, 10, 4).positions() {
+            let expected = if position.y == 3 && (3..6).contains(&position.x) {
+                Color::Red
+            } else {
+                Color::Blue
+            };
+            assert_eq!(buffer[position].bg, expected, "at {position:?}");
+        }
+        assert_eq!(buffer[(3, 2)].fg, Color::Yellow);
+        assert_eq!(buffer[(0, 0)].bg, Color::Reset);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrolled_children_straddling_edges() {
+        let panel = ["A", "B", "C", "D", "E"]
+            .into_iter()
+            .fold(
+                Panel::new_builder(None).scrollbar(false),
+                |builder, name| {
+                    builder.add_child(PanelWidget::Text(Text::from(
+                        format!("{name}1\n{name}2").as_str(),
+                    )))
+                },
+            )
+            .build();
+        let rect = Rect::new(0, 0, 4, 4);
+        let mut buffer = Buffer::empty(rect);
+        let mut state = PanelState::default();
+        state.scroll_to(3);
+
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        // B's second line is at the top and D's first at the bottom, E is out of view.
+        assert_eq!(buffer, Buffer::with_lines(["B2  ", "C1  ", "C2  ", "D1  "]));
+        assert_eq!(state.max_scroll_offset, Some(6));
+
+        state.scroll_to(u16::MAX);
+        StatefulWidget::render(&panel, rect, &mut buffer, &mut state);
+
+        assert_eq!(state.scroll_offset(), 6);
+        assert_eq!(buffer, Buffer::with_lines(["D1  ", "D2  ", "E1  ", "E2  "]));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_spacer() {
+        let panel = Panel::new_builder(None)
+            .style(Style::new().bg(Color::Blue))
+            .add_child(PanelWidget::Text(Text::from("Top")))
+            .add_child(PanelWidget::Spacer(2))
+            .add_child(PanelWidget::Text(Text::from("Bottom")))
+            .build();
+        let rect = Rect::new(0, 0, 6, 5);
+        let mut buffer = Buffer::empty(rect);
+
+        panel.render_ref(rect, &mut buffer);
+
+        assert_eq!(panel.get_height(6), 4);
+        assert_eq!(buffer[(0, 3)].symbol(), "B");
+        assert_eq!(buffer[(0, 1)].bg, Color::Blue);
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_inner_area() {
+        let area = Rect::new(2, 3, 20, 10);
 
+        let panel = Panel::new_builder(Some("Title".to_string())).build();
+        let block = Block::bordered()
+            .title("Title")
+            .padding(Padding::symmetric(2, 1));
+        assert_eq!(panel.inner_area(area), block.inner(area));
+        assert_eq!(panel.inner_area(area), Rect::new(5, 5, 14, 6));
+
+        let panel = Panel::new_builder(None).build();
+        assert_eq!(panel.inner_area(area), area);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrollbar_on_left() {
+        for title in [Some("Sidebar".to_string()), None] {
+            let name = format!("scrollbar_on_left_titled_{}", title.is_some());
+            let panel = (1..=8)
+                .fold(
+                    Panel::new_builder(title)
+                        .scrollbar_orientation(ScrollbarOrientation::VerticalLeft),
+                    |builder, i| {
+                        builder
+                            .add_child(PanelWidget::Text(Text::from(format!("Item {i}").as_str())))
+                    },
+                )
+                .build();
+            let rect = Rect::new(0, 0, 14, 6);
+            let mut buffer = Buffer::empty(rect);
+
+            panel.render_ref(rect, &mut buffer);
+
+            insta::assert_debug_snapshot!(name, buffer);
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrollbar_track_between_borders() {
+        let items = |builder: PanelBuilder<'static>| {
+            (1..=10)
+                .fold(builder, |builder, i| {
+                    builder.add_child(PanelWidget::Text(Text::from(format!("Item {i}").as_str())))
+                })
+                .build()
+        };
+        let rect = Rect::new(0, 0, 14, 7);
+
+        // Padding doesn't move the scrollbar off the border.
+        let panel =
+            items(Panel::new_builder(Some("Padded".to_string())).padding(Padding::new(1, 3, 2, 0)));
+        let mut buffer = Buffer::empty(rect);
+        panel.render_ref(rect, &mut buffer);
         insta::assert_debug_snapshot!(buffer);
+
+        // Without a top border the track starts below the title.
+        let panel = items(
+            Panel::new_builder(Some("Open".to_string()))
+                .borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM),
+        );
+        let mut buffer = Buffer::empty(rect);
+        panel.render_ref(rect, &mut buffer);
+        assert_eq!(buffer[(13, 0)].symbol(), "│");
+        assert_eq!(buffer[(13, 1)].symbol(), "↑");
+        assert_eq!(buffer[(13, 5)].symbol(), "↓");
+        assert_eq!(buffer[(13, 6)].symbol(), "┘");
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_try_build() {
+        let panel = Panel::new_builder(Some("Ok".to_string()))
+            .scrollbar_orientation(ScrollbarOrientation::VerticalLeft)
+            .add_child(PanelWidget::Text(Text::from("Hello")))
+            .try_build();
+        assert!(panel.is_ok());
+
+        let panel = Panel::new_builder(None)
+            .direction(PanelDirection::Horizontal)
+            .borders(Borders::BOTTOM)
+            .scrollbar_orientation(ScrollbarOrientation::HorizontalBottom)
+            .try_build();
+        assert!(panel.is_ok());
+
+        let panel = Panel::new_builder(None)
+            .padding_right(1)
+            .scrollbar(true)
+            .try_build();
+        assert!(panel.is_ok());
+
+        assert!(Panel::new_builder(None)
+            .scrollbar(false)
+            .try_build()
+            .is_ok());
+
+        let error = Panel::new_builder(None)
+            .scrollbar(false)
+            .scrollbar_auto(false)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(error, PanelBuildError::ScrollbarDisabled);
+
+        let error = Panel::new_builder(None)
+            .scrollbar_orientation(ScrollbarOrientation::HorizontalTop)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(error, PanelBuildError::HorizontalScrollbarOnVerticalPanel);
+        assert_eq!(
+            error.to_string(),
+            "horizontal scrollbar on a panel laid out vertically"
+        );
+
+        // The scrollbar is on by default, so it's checked the same way whether it's asked for or
+        // not.
+        for builder in [
+            Panel::new_builder(None),
+            Panel::new_builder(None).scrollbar(true),
+        ] {
+            let error = builder.try_build().unwrap_err();
+            assert_eq!(error, PanelBuildError::NoRoomForScrollbar);
+        }
+
+        let error = Panel::new_builder(Some("Left".to_string()))
+            .borders(Borders::TOP | Borders::RIGHT | Borders::BOTTOM)
+            .padding(Padding::ZERO)
+            .scrollbar_orientation(ScrollbarOrientation::VerticalLeft)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(error, PanelBuildError::NoRoomForScrollbar);
     }
 }
@@ -0,0 +1,155 @@
+//! Code for a strotui ProgressBar.
+//!
+//! A ProgressBar fills the width of its area in proportion to a ratio, with an optional label
+//! centered over it.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::WidgetRef,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Characters filling one to seven eighths of a cell from the left.
+const PARTIAL_BLOCKS: [&str; 7] = ["▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+
+#[derive(Debug)]
+pub struct ProgressBar {
+    ratio: f64,
+    label: Option<String>,
+    fill_style: Style,
+    track_style: Style,
+}
+
+impl ProgressBar {
+    /// Creates a bar filled to `ratio`, which is clamped to between 0.0 and 1.0.
+    pub fn new(ratio: f64) -> Self {
+        ProgressBar {
+            ratio: if ratio.is_nan() {
+                0.0
+            } else {
+                ratio.clamp(0.0, 1.0)
+            },
+            label: None,
+            fill_style: Style::default(),
+            track_style: Style::default(),
+        }
+    }
+
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn with_fill_style(mut self, style: Style) -> Self {
+        self.fill_style = style;
+        self
+    }
+
+    /// Style of the unfilled part of the bar.
+    pub fn with_track_style(mut self, style: Style) -> Self {
+        self.track_style = style;
+        self
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+}
+
+impl WidgetRef for ProgressBar {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        let eighths = (self.ratio * area.width as f64 * 8.0).round() as u32;
+        let (full, partial) = ((eighths / 8) as u16, (eighths % 8) as usize);
+
+        buf.set_style(area, self.track_style);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.left() + full {
+                buf[(x, y)].set_symbol("█").set_style(self.fill_style);
+            }
+            if partial > 0 {
+                buf[(area.left() + full, y)]
+                    .set_symbol(PARTIAL_BLOCKS[partial - 1])
+                    .set_style(self.fill_style);
+            }
+        }
+
+        if let Some(label) = &self.label {
+            let width = (label.width() as u16).min(area.width);
+            let x = area.left() + (area.width - width) / 2;
+            let y = area.top() + area.height.saturating_sub(1) / 2;
+            buf.set_stringn(x, y, label, width as usize, Style::default());
+            // Over the filled part the label is drawn in the fill colour's reverse so it's
+            // still readable.
+            let filled = area.left() + full;
+            if x < filled {
+                let label_fill = Rect::new(x, y, (filled - x).min(width), 1);
+                buf.set_style(label_fill, self.fill_style.add_modifier(Modifier::REVERSED));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ratatui::style::Color;
+    use tracing_test::traced_test;
+
+    fn render(bar: &ProgressBar) -> Buffer {
+        let rect = Rect::new(0, 0, 20, 1);
+        let mut buffer = Buffer::empty(rect);
+        bar.render_ref(rect, &mut buffer);
+        buffer
+    }
+
+    fn styled_bar(ratio: f64) -> ProgressBar {
+        ProgressBar::new(ratio)
+            .with_label(format!("{:.0}%", ratio * 100.0))
+            .with_fill_style(Style::new().fg(Color::Green))
+            .with_track_style(Style::new().bg(Color::DarkGray))
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_empty() {
+        insta::assert_debug_snapshot!(render(&styled_bar(0.0)));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_half() {
+        insta::assert_debug_snapshot!(render(&styled_bar(0.5)));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_full() {
+        insta::assert_debug_snapshot!(render(&styled_bar(1.0)));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_partial_cell() {
+        let buffer = render(&ProgressBar::new(0.33));
+
+        // 0.33 of 20 cells is 6.6 cells, so 6 full and 5 eighths of the next.
+        assert_eq!(buffer[(5, 0)].symbol(), "█");
+        assert_eq!(buffer[(6, 0)].symbol(), "▋");
+        assert_eq!(buffer[(7, 0)].symbol(), " ");
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_ratio_clamped() {
+        assert_eq!(ProgressBar::new(-1.0).ratio(), 0.0);
+        assert_eq!(ProgressBar::new(2.5).ratio(), 1.0);
+        assert_eq!(ProgressBar::new(f64::NAN).ratio(), 0.0);
+    }
+}
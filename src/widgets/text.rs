@@ -1,12 +1,34 @@
 //! A simple text widget that displays text that can over multiple lines or truncated to fit the
 //! width of the widget.
 
-use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::WidgetRef};
+mod ansi;
+
+use std::{borrow::Cow, cell::RefCell, ops::Range};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::WidgetRef,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Theme;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TextWrap {
     Truncate,
+    TruncateWord,
     TruncateWithEllipsis,
+    TruncateMiddleEllipsis,
+    TruncateLeftEllipsis,
+    PreformattedClip,
     Wrapped,
     WrappedWords,
     WrappedJustified,
@@ -14,311 +36,2822 @@ pub enum TextWrap {
     WrappedRightAligned,
 }
 
+/// Where text, or the children of a panel, sit vertically within an area they don't fill.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VerticalAlignment {
+    #[default]
+    Top,
+    Center,
+    Bottom,
+}
+
+/// The direction text reads in. Right to left text starts each line at the right edge.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextDirection {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
 /// A simple text widget that displays text that can be either
 /// (a) Truncated to fit the width of the widget, or
-/// (b) Truncated to fit the width of the widget with an ellipsis,
-/// (c) Wrapped to fit the width of the widget exactly,
-/// (d) Wrapped to fit the width of the widget at word boundaries (default),
-/// (e) Wrapped and justified to fit the width of the widget as word boundaries
-/// (f) Wrapped and centered to fit the width of the widget as word boundaries, or
-/// (g) Wrapped and right-aligned to fit the width of the widget as word boundaries.
+/// (b) Truncated after the last whole word that fits the width of the widget,
+/// (c) Truncated to fit the width of the widget with an ellipsis,
+/// (d) Truncated to fit the width of the widget with an ellipsis in the middle,
+/// (e) Truncated to fit the width of the widget with an ellipsis at the start,
+/// (f) Split only at line breaks with each line truncated to fit the width of the widget,
+/// (g) Wrapped to fit the width of the widget exactly,
+/// (h) Wrapped to fit the width of the widget at word boundaries (default),
+/// (i) Wrapped and justified to fit the width of the widget as word boundaries
+/// (j) Wrapped and centered to fit the width of the widget as word boundaries, or
+/// (k) Wrapped and right-aligned to fit the width of the widget as word boundaries.
+///
+/// When wrapping at word boundaries, words too long to fit on a line are broken so they fill it
+/// and the rest of the word starts the next line. Right-aligned and centered lines are aligned
+/// after breaking, so it's only the rest of the word that's moved across.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Text {
     text: String,
     wrap: TextWrap,
-    // TODO: line_numbers: bool,
+    style: Style,
+    // Byte ranges of the text that are styled differently, patched on top of `style`. Empty when
+    // the text is all one style.
+    spans: Vec<(Range<usize>, Style)>,
+    line_numbers: bool,
+    tab_width: u16,
+    ellipsis: String,
+    max_lines: usize,
+    vertical_alignment: VerticalAlignment,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote"))]
+    alignment: Alignment,
+    direction: TextDirection,
+    // Byte ranges of the text that match the highlight, these are rendered with the highlight
+    // style patched on top of their usual style.
+    highlights: Vec<Range<usize>>,
+    highlight_style: Style,
+    trim_trailing: bool,
+    break_after: Vec<char>,
+    overflow_indicator: bool,
+    // The byte ranges of the lines from the last time the text was wrapped and the width it was
+    // wrapped at. Panels get the height of the text before rendering it so this saves wrapping
+    // it twice.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    lines_cache: RefCell<Option<(u16, Vec<Range<usize>>)>>,
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Self::new(String::new())
+    }
+}
+
+/// The fields of a [`Text`] as they're read in, any that are missing take the same defaults as
+/// `Text::new`. The byte ranges are checked against the text before it's handed out.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(remote = "Text", default = "Text::default")]
+struct UncheckedText {
+    text: String,
+    wrap: TextWrap,
+    style: Style,
+    spans: Vec<(Range<usize>, Style)>,
+    line_numbers: bool,
+    tab_width: u16,
+    ellipsis: String,
+    max_lines: usize,
+    vertical_alignment: VerticalAlignment,
+    #[serde(with = "crate::serde_remote")]
+    alignment: Alignment,
+    direction: TextDirection,
+    highlights: Vec<Range<usize>>,
+    highlight_style: Style,
+    trim_trailing: bool,
+    break_after: Vec<char>,
+    overflow_indicator: bool,
+    #[serde(skip)]
+    lines_cache: RefCell<Option<(u16, Vec<Range<usize>>)>>,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Text {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = UncheckedText::deserialize(deserializer)?;
+        let ranges = text.spans.iter().map(|(range, _)| range);
+
+        if let Some(range) = ranges.chain(&text.highlights).find(|range| {
+            range.start > range.end
+                || !text.text.is_char_boundary(range.start)
+                || !text.text.is_char_boundary(range.end)
+        }) {
+            return Err(serde::de::Error::custom(format!(
+                "byte range {range:?} isn't within the text or splits a character"
+            )));
+        }
+
+        Ok(text)
+    }
+}
+
+/// Builds up a `Text` with all its options set in one go, any options that aren't set take the
+/// same defaults as `Text::new`.
+///
+/// ```
+/// use ratatui::style::{Color, Style};
+/// use strotui::{Text, TextWrap};
+///
+/// let text = Text::new_builder("Some text to show".to_string())
+///     .wrap(TextWrap::WrappedJustified)
+///     .style(Style::default().fg(Color::Green))
+///     .max_lines(3)
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct TextBuilder {
+    pub text: String,
+    pub wrap: Option<TextWrap>,
+    pub style: Option<Style>,
+    pub line_numbers: bool,
+    pub tab_width: Option<u16>,
+    pub ellipsis: Option<String>,
+    pub max_lines: Option<usize>,
+    pub vertical_alignment: Option<VerticalAlignment>,
+    pub alignment: Option<Alignment>,
+    pub direction: Option<TextDirection>,
+    pub trim_trailing: Option<bool>,
+    pub break_after: Option<Vec<char>>,
+    pub overflow_indicator: bool,
+    pub theme: Option<Theme>,
+}
+
+impl TextBuilder {
+    pub fn text(mut self, text: String) -> Self {
+        self.text = text;
+        self
+    }
+
+    pub fn wrap(mut self, wrap: TextWrap) -> Self {
+        self.wrap = Some(wrap);
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn line_numbers(mut self, enabled: bool) -> Self {
+        self.line_numbers = enabled;
+        self
+    }
+
+    pub fn tab_width(mut self, cols: u16) -> Self {
+        self.tab_width = Some(cols);
+        self
+    }
+
+    pub fn ellipsis(mut self, ellipsis: String) -> Self {
+        self.ellipsis = Some(ellipsis);
+        self
+    }
+
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
+
+    pub fn vertical_alignment(mut self, alignment: VerticalAlignment) -> Self {
+        self.vertical_alignment = Some(alignment);
+        self
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    pub fn direction(mut self, direction: TextDirection) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    pub fn trim_trailing(mut self, trim: bool) -> Self {
+        self.trim_trailing = Some(trim);
+        self
+    }
+
+    pub fn break_after(mut self, chars: Vec<char>) -> Self {
+        self.break_after = Some(chars);
+        self
+    }
+
+    pub fn overflow_indicator(mut self, enabled: bool) -> Self {
+        self.overflow_indicator = enabled;
+        self
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    pub fn build(self) -> Text {
+        let mut text = Text::new_with_wrap(self.text, self.wrap.unwrap_or(TextWrap::WrappedWords))
+            .with_line_numbers(self.line_numbers)
+            .with_overflow_indicator(self.overflow_indicator);
+
+        if let Some(style) = self.style {
+            text = text.with_style(style);
+        }
+        if let Some(cols) = self.tab_width {
+            text = text.with_tab_width(cols);
+        }
+        if let Some(ellipsis) = self.ellipsis {
+            text = text.with_ellipsis(ellipsis);
+        }
+        if let Some(max_lines) = self.max_lines {
+            text = text.with_max_lines(max_lines);
+        }
+        if let Some(alignment) = self.vertical_alignment {
+            text = text.with_vertical_alignment(alignment);
+        }
+        if let Some(alignment) = self.alignment {
+            text = text.with_alignment(alignment);
+        }
+        if let Some(direction) = self.direction {
+            text = text.with_direction(direction);
+        }
+        if let Some(trim) = self.trim_trailing {
+            text = text.with_trim_trailing(trim);
+        }
+        if let Some(chars) = self.break_after {
+            text = text.with_break_after(chars);
+        }
+        if let Some(theme) = &self.theme {
+            text = text.with_theme(theme);
+        }
+
+        text
+    }
 }
 
 impl<'a> Text {
     pub fn new(text: String) -> Self {
-        Self {
+        Self::new_with_wrap(text, TextWrap::WrappedWords)
+    }
+
+    pub fn new_builder(text: String) -> TextBuilder {
+        TextBuilder {
             text,
-            wrap: TextWrap::WrappedWords,
+            ..Default::default()
         }
     }
 
     pub fn new_with_wrap(text: String, wrap: TextWrap) -> Self {
-        Self { text, wrap }
+        Self {
+            text,
+            wrap,
+            style: Style::default(),
+            spans: Vec::new(),
+            line_numbers: false,
+            tab_width: 4,
+            ellipsis: "...".to_string(),
+            max_lines: 0,
+            vertical_alignment: VerticalAlignment::Top,
+            alignment: Alignment::Left,
+            direction: TextDirection::Ltr,
+            highlights: Vec::new(),
+            highlight_style: Style::default(),
+            trim_trailing: true,
+            break_after: vec!['-', '/'],
+            overflow_indicator: false,
+            lines_cache: RefCell::new(None),
+        }
+    }
+
+    /// Create text from a number of spans that can each have their own style. The spans are
+    /// wrapped as one piece of text and a span broken over several lines keeps its style on each.
+    pub fn from_spans(spans: Vec<Span<'static>>) -> Self {
+        let mut text = String::new();
+        let mut ranges = Vec::with_capacity(spans.len());
+
+        for span in spans {
+            let start = text.len();
+            text.push_str(&span.content);
+            ranges.push((start..text.len(), span.style));
+        }
+
+        let mut ret = Self::new(text);
+        ret.spans = ranges;
+        ret
+    }
+
+    /// Create text with each of the lines on its own line, e.g. lines read from a log.
+    pub fn from_lines(lines: Vec<String>) -> Self {
+        Self::new(lines.join("\n"))
+    }
+
+    /// Create text from a string containing ANSI escape sequences, e.g. the coloured output of a
+    /// program. SGR sequences (colours, bold, underline, etc) style the text that follows them
+    /// and all escape sequences are removed from the text.
+    pub fn from_ansi(text: &str) -> Self {
+        Self::from_spans(ansi::parse_ansi(text))
+    }
+
+    /// Set the style that all the text is rendered with.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// The same as [`Text::with_style`].
+    pub fn style(self, style: Style) -> Self {
+        self.with_style(style)
+    }
+
+    /// Fill in anything not already set in the style from the theme's text style.
+    pub fn with_theme(mut self, theme: &Theme) -> Self {
+        self.style = theme.text_style.patch(self.style);
+        self
+    }
+
+    /// Show line numbers in a gutter to the left of the text. The numbers are those of the lines
+    /// in the original text, so when a line is wrapped only its first visual line is numbered and
+    /// the continuation lines have a blank gutter.
+    pub fn with_line_numbers(mut self, enabled: bool) -> Self {
+        self.line_numbers = enabled;
+        self
+    }
+
+    /// Set the number of columns between tab stops, tabs are expanded to spaces up to the next
+    /// stop from the start of each line. Defaults to 4.
+    pub fn with_tab_width(mut self, cols: u16) -> Self {
+        self.tab_width = cols;
+        self.invalidate_lines_cache();
+        self
+    }
+
+    /// Set the string shown at the end of truncated text when using
+    /// `TextWrap::TruncateWithEllipsis`. Defaults to `"..."`.
+    pub fn with_ellipsis(mut self, ellipsis: String) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    /// Limit the number of lines the text wraps onto, if there's more text than fits the last line
+    /// ends with the ellipsis. A limit of 0 (the default) means there's no limit.
+    pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines;
+        self.invalidate_lines_cache();
+        self
+    }
+
+    /// Set where the text sits vertically when it's shorter than the area it's rendered in, when
+    /// it's taller it's always rendered from the top. Defaults to `VerticalAlignment::Top`.
+    pub fn with_vertical_alignment(mut self, alignment: VerticalAlignment) -> Self {
+        self.vertical_alignment = alignment;
+        self
+    }
+
+    /// Set where truncated text sits horizontally when it's narrower than the area, this only
+    /// applies to the truncating modes as the wrapping modes have their own alignment. Defaults to
+    /// `Alignment::Left`.
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Right to left text is wrapped in the same way as left to right, but each line is shown
+    /// starting from the right edge with its characters running from right to left. Any line
+    /// numbers stay on the left.
+    pub fn with_direction(mut self, direction: TextDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// The lines the text wraps onto at the given width (including any line numbers), as they
+    /// would be rendered but without any line numbers or the padding used to align them.
+    pub fn wrapped_lines(&'a self, width: u16) -> impl Iterator<Item = Cow<'a, str>> + 'a {
+        let width = width.saturating_sub(self.gutter_width());
+
+        self.get_line_slices(width)
+            .into_iter()
+            .map(move |slice| line_content(self.to_line(slice, width)))
+    }
+
+    /// Highlight everywhere the needle appears in the text, ignoring case, by rendering it with
+    /// the given style. Matches that are wrapped over several lines are highlighted on each.
+    pub fn with_highlight(mut self, needle: String, style: Style) -> Self {
+        self.highlights = find_matches_ignore_case(&self.text, &needle);
+        self.highlight_style = style;
+        self
+    }
+
+    /// Whether to remove whitespace from the end of each line after wrapping so it isn't rendered,
+    /// this matters when the text has a background. Leading whitespace is always kept. Defaults to
+    /// true.
+    pub fn with_trim_trailing(mut self, trim: bool) -> Self {
+        self.trim_trailing = trim;
+        self.invalidate_lines_cache();
+        self
+    }
+
+    /// Set the characters words can be broken after when wrapping at word boundaries, the
+    /// character is kept at the end of the line. Defaults to hyphens and slashes.
+    pub fn with_break_after(mut self, chars: Vec<char>) -> Self {
+        self.break_after = chars;
+        self.invalidate_lines_cache();
+        self
+    }
+
+    /// End the last row with the ellipsis when there are more lines than fit in the area the text
+    /// is rendered into, so it's clear some were left out. This is for text rendered directly,
+    /// panels scroll to show the rest instead. Defaults to false.
+    pub fn with_overflow_indicator(mut self, enabled: bool) -> Self {
+        self.overflow_indicator = enabled;
+        self
+    }
+
+    /// The same as [`Text::with_overflow_indicator`].
+    pub fn overflow_indicator(self, enabled: bool) -> Self {
+        self.with_overflow_indicator(enabled)
+    }
+
+    /// Replace the text in place. Any styled spans or highlights were for the old text so they're
+    /// removed, the style of the text as a whole is kept.
+    pub fn set_text(&mut self, text: String) {
+        self.text = text;
+        self.spans.clear();
+        self.highlights.clear();
+        self.invalidate_lines_cache();
+    }
+
+    pub fn set_wrap(&mut self, wrap: TextWrap) {
+        self.wrap = wrap;
+        self.invalidate_lines_cache();
+    }
+
+    /// The width and height the text takes up when wrapped to fit within `max_width` columns,
+    /// including any line numbers. The width is that of the longest line it wraps onto.
+    pub fn measure(&self, max_width: u16) -> (u16, u16) {
+        let width = self
+            .wrapped_lines(max_width)
+            .map(|line| text_width(&line, self.tab_width()))
+            .max()
+            .unwrap_or(0);
+        let width = u16::try_from(width)
+            .unwrap_or(u16::MAX)
+            .saturating_add(self.gutter_width())
+            .min(max_width);
+
+        (width, self.get_height(max_width))
+    }
+
+    /// The number of lines the text takes up when rendered `width` columns wide, including any
+    /// line numbers.
+    ///
+    /// ```
+    /// use strotui::Text;
+    ///
+    /// let text = Text::from("Let's wrap this text that is long enough.");
+    /// assert_eq!(text.line_count(20), 2);
+    /// ```
+    pub fn line_count(&self, width: u16) -> u16 {
+        self.get_height(width)
+    }
+
+    /// Whether there's no text at all to show.
+    ///
+    /// ```
+    /// use strotui::Text;
+    ///
+    /// assert!(Text::from("").is_empty());
+    /// assert!(!Text::from(" ").is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub(crate) fn get_height(&self, width: u16) -> u16 {
+        let width = width.saturating_sub(self.gutter_width());
+        // Saturate rather than wrap around for huge amounts of text so the height still makes
+        // sense for laying things out.
+        u16::try_from(self.get_line_slices(width).len()).unwrap_or(u16::MAX)
+    }
+
+    /// Width of the longest line when nothing is wrapped, including any line numbers.
+    pub(crate) fn get_width(&self) -> u16 {
+        let width = split_lines(&self.text)
+            .map(|line| text_width(line, self.tab_width()))
+            .max()
+            .unwrap_or(0);
+        u16::try_from(width)
+            .unwrap_or(u16::MAX)
+            .saturating_add(self.gutter_width())
+    }
+
+    fn tab_width(&self) -> usize {
+        self.tab_width as usize
+    }
+
+    /// The width taken up by the line numbers and their separator, zero if they're not shown.
+    fn gutter_width(&self) -> u16 {
+        if self.line_numbers {
+            let count = split_lines(&self.text).count().max(1);
+            count.to_string().len() as u16 + 1
+        } else {
+            0
+        }
+    }
+
+    /// Must be called whenever anything that changes how the text wraps is changed.
+    fn invalidate_lines_cache(&mut self) {
+        *self.lines_cache.get_mut() = None;
+    }
+
+    // Every wrapping mode produces lines that are slices of the original text, these are turned
+    // into styled lines for rendering by `to_line`. The lines are cached for the last width they
+    // were wrapped at.
+    fn get_line_slices(&'a self, width: u16) -> Vec<&'a str> {
+        if let Some((cached_width, ranges)) = &*self.lines_cache.borrow() {
+            if *cached_width == width {
+                return ranges.iter().map(|r| &self.text[r.clone()]).collect();
+            }
+        }
+
+        let lines = self.wrap_lines(width);
+
+        let ranges = lines
+            .iter()
+            .map(|line| {
+                let start = self.offset_of(line);
+                start..start + line.len()
+            })
+            .collect();
+        *self.lines_cache.borrow_mut() = Some((width, ranges));
+
+        lines
+    }
+
+    fn wrap_lines(&'a self, width: u16) -> Vec<&'a str> {
+        tracing::trace!("Getting lines at width {} for text: {:?}", width, self);
+
+        let mut lines = match self.wrap {
+            TextWrap::Truncate => self.get_lines_truncate(width),
+            TextWrap::TruncateWord => self.get_lines_truncate_word(width),
+            // Ellipses are handled by the renderer
+            TextWrap::TruncateWithEllipsis
+            | TextWrap::TruncateMiddleEllipsis
+            | TextWrap::TruncateLeftEllipsis => self.get_lines_truncate(width),
+            TextWrap::PreformattedClip => self.get_lines_preformatted_clip(width),
+            TextWrap::Wrapped => self.get_lines_wrapped(width),
+            TextWrap::WrappedWords => self.get_lines_wrapped_words(width),
+            TextWrap::WrappedJustified => self.get_lines_wrapped_justified(width),
+            TextWrap::WrappedCentered => self.get_lines_wrapped_centered(width),
+            TextWrap::WrappedRightAligned => self.get_lines_wrapped_right_aligned(width),
+        };
+
+        if self.trim_trailing {
+            lines = lines.into_iter().map(str::trim_end).collect();
+        }
+
+        if self.max_lines > 0 {
+            lines.truncate(self.max_lines);
+        }
+
+        lines
+    }
+
+    /// Whether the wrapped lines stop before the end of the text because of `max_lines`.
+    fn is_cut_short(&self, slices: &[&str]) -> bool {
+        match slices.last() {
+            Some(last) if self.max_lines > 0 && slices.len() == self.max_lines => {
+                let end = self.offset_of(last) + last.len();
+                !self.text[end..].trim().is_empty()
+            }
+            _ => false,
+        }
+    }
+
+    /// Turn a wrapped slice of the text into a line ready for rendering. Most wrapping modes just
+    /// need the styles applying, but when justifying we have to insert extra spaces too.
+    fn to_line(&'a self, slice: &'a str, width: u16) -> Line<'a> {
+        if let TextWrap::WrappedJustified = self.wrap {
+            let end = self.offset_of(slice) + slice.len();
+            let rest = self.text[end..].trim_start_matches(' ');
+
+            // The last line of a paragraph is left aligned rather than stretched.
+            if !rest.is_empty() && !rest.starts_with(['\r', '\n']) {
+                return self.justify_line(slice, width);
+            }
+        }
+
+        self.styled_line(slice)
+    }
+
+    /// Where a slice of `self.text` starts in the text, all wrapped lines are slices of the text
+    /// so we can find this from the pointers.
+    fn offset_of(&self, slice: &str) -> usize {
+        slice.as_ptr() as usize - self.text.as_ptr() as usize
+    }
+
+    /// Turn a slice of the text into a line, splitting it into spans wherever the style changes.
+    /// Lines that were broken at a soft hyphen end with a hyphen.
+    fn styled_line(&'a self, line: &'a str) -> Line<'a> {
+        let hyphenated = line.ends_with(SOFT_HYPHEN);
+
+        if self.spans.is_empty() && self.highlights.is_empty() {
+            let (mut content, _) = displayed_text(line, 0, self.tab_width());
+            if hyphenated {
+                content.to_mut().push('-');
+            }
+            return Line::from(Span::raw(content)).style(self.style);
+        }
+
+        let start = self.offset_of(line);
+        let end = start + line.len();
+
+        let mut column = 0;
+        let mut spans = self
+            .style_runs(start, end)
+            .into_iter()
+            .map(|(range, style)| {
+                let (content, next) = displayed_text(&self.text[range], column, self.tab_width());
+                column = next;
+                Span::styled(content, style)
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(last) = spans.last_mut().filter(|_| hyphenated) {
+            last.content.to_mut().push('-');
+        }
+
+        Line::from(spans).style(self.style)
+    }
+
+    /// The byte ranges between `start` and `end` that each have a single style, taking into
+    /// account both the spans and any highlighted matches.
+    fn style_runs(&self, start: usize, end: usize) -> Vec<(Range<usize>, Style)> {
+        let whole = [(0..self.text.len(), Style::default())];
+        let spans = if self.spans.is_empty() {
+            &whole[..]
+        } else {
+            &self.spans[..]
+        };
+
+        let mut runs = vec![];
+
+        for (range, style) in spans {
+            let from = range.start.max(start);
+            let to = range.end.min(end);
+            if from >= to {
+                continue;
+            }
+
+            let mut pos = from;
+            for highlight in self.highlights.iter() {
+                let highlight_from = highlight.start.max(pos);
+                let highlight_to = highlight.end.min(to);
+                if highlight_from >= highlight_to {
+                    continue;
+                }
+
+                if pos < highlight_from {
+                    runs.push((pos..highlight_from, *style));
+                }
+                runs.push((
+                    highlight_from..highlight_to,
+                    style.patch(self.highlight_style),
+                ));
+                pos = highlight_to;
+            }
+
+            if pos < to {
+                runs.push((pos..to, *style));
+            }
+        }
+
+        runs
+    }
+
+    /// The line number of each wrapped line, or `None` for lines that continue a wrapped line.
+    fn line_numbers(&self, slices: &[&str]) -> Vec<Option<usize>> {
+        let mut number = 0;
+
+        slices
+            .iter()
+            .map(|slice| {
+                let start = self.offset_of(slice);
+                let starts_line = start == 0 || self.text[..start].ends_with(['\r', '\n']);
+                if starts_line {
+                    number += 1;
+                }
+
+                starts_line.then_some(number)
+            })
+            .collect()
+    }
+
+    // Truncated text is a single line so anything after a line break isn't shown. Lines are
+    // always slices of the text, even when it's empty, so `offset_of` can find them.
+    fn get_lines_truncate(&'a self, width: u16) -> Vec<&'a str> {
+        let line = split_lines(&self.text).next().unwrap_or(&self.text[..0]);
+        let end = byte_index_at_width(line, width as usize, self.tab_width());
+        vec![&line[..end]]
+    }
+
+    fn get_lines_preformatted_clip(&'a self, width: u16) -> Vec<&'a str> {
+        split_lines(&self.text)
+            .map(|line| &line[..byte_index_at_width(line, width as usize, self.tab_width())])
+            .collect()
+    }
+
+    // This is the first line the text wraps onto a word at a time, so it's cut in the middle of
+    // the first word if that doesn't fit.
+    fn get_lines_truncate_word(&'a self, width: u16) -> Vec<&'a str> {
+        let first = wrap_words(&self.text, width, self.tab_width(), &self.break_after)
+            .into_iter()
+            .next()
+            .unwrap_or(&self.text[..0]);
+        vec![first.trim_end()]
+    }
+
+    fn get_lines_wrapped(&'a self, width: u16) -> Vec<&'a str> {
+        let mut pos = 0;
+        let mut lines = vec![];
+
+        while pos < self.text.len() {
+            let end = pos + wrap_index(&self.text[pos..], width as usize, self.tab_width());
+            // Nothing fits when the width is zero.
+            if end == pos {
+                break;
+            }
+            let line = &self.text[pos..end];
+
+            if let Some((to, len)) = find_line_break(line) {
+                lines.push(&line[..to]);
+                pos += to + len;
+                continue;
+            }
+
+            lines.push(line);
+
+            pos = skip_line_break(&self.text, end);
+        }
+
+        tracing::trace!("Lines: {:?}", lines);
+
+        lines
+    }
+
+    fn get_lines_wrapped_words(&'a self, width: u16) -> Vec<&'a str> {
+        let lines = wrap_words(&self.text, width, self.tab_width(), &self.break_after);
+
+        tracing::trace!("Lines: {:?}", lines);
+
+        lines
+    }
+
+    // Lines are wrapped a paragraph at a time, the justification itself is done when the lines are
+    // styled, see `to_line`.
+    fn get_lines_wrapped_justified(&'a self, width: u16) -> Vec<&'a str> {
+        let mut lines = vec![];
+
+        for paragraph in split_lines(&self.text) {
+            if paragraph.is_empty() {
+                lines.push(paragraph);
+            } else {
+                lines.extend(wrap_words(
+                    paragraph,
+                    width,
+                    self.tab_width(),
+                    &self.break_after,
+                ));
+            }
+        }
+
+        tracing::trace!("Lines: {:?}", lines);
+
+        lines
+    }
+
+    // The centering itself is done by the renderer, see `line_offset`.
+    fn get_lines_wrapped_centered(&'a self, width: u16) -> Vec<&'a str> {
+        let lines = wrap_words_trimmed(&self.text, width, self.tab_width(), &self.break_after);
+
+        tracing::trace!("Lines: {:?}", lines);
+
+        lines
     }
 
-    pub(crate) fn get_height(&self, width: u16) -> u16 {
-        self.get_lines(width).len() as u16
+    // As with centering the alignment is done by the renderer, see `line_offset`.
+    fn get_lines_wrapped_right_aligned(&'a self, width: u16) -> Vec<&'a str> {
+        let lines = wrap_words_trimmed(&self.text, width, self.tab_width(), &self.break_after);
+
+        tracing::trace!("Lines: {:?}", lines);
+
+        lines
+    }
+
+    /// Stretch a line to exactly the width by spreading extra spaces between the words, any
+    /// spaces that don't divide evenly go to the leftmost gaps. Lines with a single word, or that
+    /// are already too long, are left as they are.
+    fn justify_line(&'a self, line: &'a str, width: u16) -> Line<'a> {
+        let words = line
+            .split(' ')
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<_>>();
+        let gaps = words.len().saturating_sub(1);
+        // The hyphen shown for a soft hyphen at the end of the line isn't part of the text.
+        let hyphen = usize::from(line.ends_with(SOFT_HYPHEN));
+        let words_len = hyphen
+            + words
+                .iter()
+                .map(|w| text_width(w, self.tab_width()))
+                .sum::<usize>();
+
+        if gaps == 0 || words_len + gaps > width as usize {
+            return self.styled_line(line);
+        }
+
+        let spaces = width as usize - words_len;
+        let (base, extra) = (spaces / gaps, spaces % gaps);
+
+        let mut spans = vec![];
+        for (i, word) in words.into_iter().enumerate() {
+            if i > 0 {
+                let gap = base + if i <= extra { 1 } else { 0 };
+                spans.push(Span::raw(" ".repeat(gap)));
+            }
+            spans.extend(self.styled_line(word).spans);
+        }
+
+        Line::from(spans).style(self.style)
+    }
+
+    /// How far in from the left of the area a line should be rendered, for most wrapping modes
+    /// this is zero but aligned modes will shift the line across. Lines that are wider than the
+    /// area are never shifted left of it.
+    fn line_offset(&self, line: &Line, width: u16) -> u16 {
+        let spare = width.saturating_sub(line.width() as u16);
+
+        match self.wrap {
+            // An odd column left over goes on the right.
+            TextWrap::WrappedCentered => spare / 2,
+            TextWrap::WrappedRightAligned => spare,
+            TextWrap::Truncate
+            | TextWrap::TruncateWord
+            | TextWrap::TruncateWithEllipsis
+            | TextWrap::TruncateMiddleEllipsis
+            | TextWrap::TruncateLeftEllipsis
+            | TextWrap::PreformattedClip => match self.alignment {
+                Alignment::Left => 0,
+                Alignment::Center => spare / 2,
+                Alignment::Right => spare,
+            },
+            _ => 0,
+        }
+    }
+}
+
+/// A soft hyphen marks where a word can be broken when wrapping, it's shown as a hyphen if the
+/// word is broken there and not shown at all otherwise.
+const SOFT_HYPHEN: char = '\u{ad}';
+const SOFT_HYPHEN_STR: &str = "\u{ad}";
+
+/// The number of columns a grapheme cluster takes up when rendered at the given column, this is
+/// the width of the cluster except for tabs which take up enough to reach the next tab stop and
+/// soft hyphens which aren't shown.
+fn grapheme_width(grapheme: &str, column: usize, tab_width: usize) -> usize {
+    match grapheme {
+        "\t" => tab_width - column % tab_width.max(1),
+        SOFT_HYPHEN_STR => 0,
+        _ => grapheme.width(),
+    }
+}
+
+/// Copy the cells in `area` from one buffer to the other flipped left to right. Wide characters
+/// are moved as a whole so they still read correctly.
+fn mirror_cells(from: &Buffer, to: &mut Buffer, area: Rect) {
+    for y in area.top()..area.bottom() {
+        let mut x = area.left();
+        while x < area.right() {
+            let width = (from[(x, y)].symbol().width() as u16).clamp(1, area.right() - x);
+            let mirrored = area.left() + area.right() - x - width;
+            for i in 0..width {
+                to[(mirrored + i, y)] = from[(x + i, y)].clone();
+            }
+            x += width;
+        }
+    }
+}
+
+/// The number of columns the text takes up when rendered, wide characters (e.g. CJK) take up two
+/// columns. This is measured per grapheme cluster in the same way the buffer measures cells.
+fn text_width(text: &str, tab_width: usize) -> usize {
+    text.graphemes(true)
+        .fold(0, |used, g| used + grapheme_width(g, used, tab_width))
+}
+
+/// The byte index into the text after as many grapheme clusters as fit within the width in
+/// columns, or the end of the text if it all fits. This is never inside a grapheme cluster so is
+/// safe to slice at and won't split up combining marks or emoji sequences.
+fn byte_index_at_width(text: &str, width: usize, tab_width: usize) -> usize {
+    let mut used = 0;
+
+    for (i, g) in text.grapheme_indices(true) {
+        used += grapheme_width(g, used, tab_width);
+        if used > width {
+            return i;
+        }
+    }
+
+    text.len()
+}
+
+/// The byte index into the text such that the text from there to the end fits within the width,
+/// this is `byte_index_at_width` working back from the end of the text. Tabs are measured as a
+/// full tab stop as we can't know which column they'll start at.
+fn byte_index_from_end_at_width(text: &str, width: usize, tab_width: usize) -> usize {
+    let mut used = 0;
+
+    for (i, g) in text.grapheme_indices(true).rev() {
+        used += grapheme_width(g, 0, tab_width);
+        if used > width {
+            return i + g.len();
+        }
+    }
+
+    0
+}
+
+/// As `byte_index_at_width` but always includes at least one grapheme cluster for a non-zero
+/// width, so that wrapping makes progress even when a wide character doesn't fit.
+fn wrap_index(text: &str, width: usize, tab_width: usize) -> usize {
+    match byte_index_at_width(text, width, tab_width) {
+        0 if width > 0 => text.graphemes(true).next().map(str::len).unwrap_or(0),
+        end => end,
+    }
+}
+
+/// The text as it's displayed, with any tabs replaced with enough spaces to reach the next tab
+/// stop starting from the given column and any soft hyphens removed. Returns the new text and the
+/// column after it.
+fn displayed_text<'t>(text: &'t str, column: usize, tab_width: usize) -> (Cow<'t, str>, usize) {
+    if !text.contains(['\t', SOFT_HYPHEN]) {
+        return (Cow::Borrowed(text), column + text_width(text, tab_width));
+    }
+
+    let mut displayed = String::with_capacity(text.len());
+    let mut column = column;
+
+    for g in text.graphemes(true) {
+        let width = grapheme_width(g, column, tab_width);
+        match g {
+            "\t" => displayed.push_str(&" ".repeat(width)),
+            SOFT_HYPHEN_STR => {}
+            _ => displayed.push_str(g),
+        }
+        column += width;
+    }
+
+    (Cow::Owned(displayed), column)
+}
+
+/// The text of a line, this only allocates when the line is made up of several spans.
+fn line_content(line: Line<'_>) -> Cow<'_, str> {
+    let mut spans = line.spans;
+
+    if spans.len() == 1 {
+        spans.remove(0).content
+    } else {
+        Cow::Owned(spans.iter().map(|span| span.content.as_ref()).collect())
+    }
+}
+
+/// Find the byte ranges of everywhere the needle appears in the text ignoring case, the matches
+/// don't overlap.
+fn find_matches_ignore_case(text: &str, needle: &str) -> Vec<Range<usize>> {
+    let mut matches = vec![];
+    if needle.is_empty() {
+        return matches;
+    }
+
+    let mut pos = 0;
+    while let Some(c) = text[pos..].chars().next() {
+        match match_len_ignore_case(&text[pos..], needle) {
+            Some(len) => {
+                matches.push(pos..pos + len);
+                pos += len;
+            }
+            None => pos += c.len_utf8(),
+        }
+    }
+
+    matches
+}
+
+/// If the text starts with the needle, ignoring case, the length in bytes of the matching text.
+fn match_len_ignore_case(text: &str, needle: &str) -> Option<usize> {
+    let mut text_chars = text.char_indices();
+    let mut len = 0;
+
+    for n in needle.chars() {
+        let (i, c) = text_chars.next()?;
+        if c != n && !c.to_lowercase().eq(n.to_lowercase()) {
+            return None;
+        }
+        len = i + c.len_utf8();
+    }
+
+    Some(len)
+}
+
+/// Find the first line break in the text, returning where it is and how long it is. Line breaks
+/// can be any of `\n`, `\r\n` or a lone `\r`.
+fn find_line_break(text: &str) -> Option<(usize, usize)> {
+    let at = text.find(['\r', '\n'])?;
+
+    if text[at..].starts_with("\r\n") {
+        Some((at, 2))
+    } else {
+        Some((at, 1))
+    }
+}
+
+/// If the text at `pos` starts with a line break, the position after it. This is used after a
+/// line that has been wrapped so that a line break straight after it doesn't start an extra empty
+/// line.
+fn skip_line_break(text: &str, pos: usize) -> usize {
+    let rest = &text[pos..];
+
+    if rest.starts_with("\r\n") {
+        pos + 2
+    } else if rest.starts_with(['\r', '\n']) {
+        pos + 1
+    } else {
+        pos
+    }
+}
+
+/// Split the text into lines at any kind of line break, like `str::lines` a final line break
+/// doesn't produce an extra empty line.
+fn split_lines(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let line = match find_line_break(rest) {
+            Some((to, len)) => {
+                let line = &rest[..to];
+                rest = &rest[to + len..];
+                line
+            }
+            None => std::mem::take(&mut rest),
+        };
+
+        Some(line)
+    })
+}
+
+/// Wrap the text at word boundaries so that each line fits within the width, words can also be
+/// broken at soft hyphens and after any of the `break_after` characters. Words longer than the
+/// width are split across lines.
+fn wrap_words<'t>(
+    text: &'t str,
+    width: u16,
+    tab_width: usize,
+    break_after: &[char],
+) -> Vec<&'t str> {
+    let mut pos = 0;
+    let mut lines = vec![];
+
+    while pos < text.len() {
+        let end = pos + wrap_index(&text[pos..], width as usize, tab_width);
+        // Nothing fits when the width is zero.
+        if end == pos {
+            break;
+        }
+        let line = &text[pos..end];
+
+        let to = if text.len() == end || text[end..].starts_with(' ') {
+            end
+        } else {
+            [
+                line.rfind(' '),
+                soft_hyphen_break(line, width, tab_width),
+                break_after_char(line, break_after),
+            ]
+            .into_iter()
+            .flatten()
+            .max()
+            .map(|y| pos + y)
+            .unwrap_or(end)
+        };
+
+        if let Some((to, len)) = find_line_break(line) {
+            lines.push(&line[..to]);
+            pos += to + len;
+            continue;
+        }
+
+        lines.push(&text[pos..to]);
+
+        pos = to;
+
+        while text[pos..].starts_with(' ') {
+            pos += 1;
+        }
+
+        pos = skip_line_break(text, pos);
+    }
+
+    lines
+}
+
+/// Find the last soft hyphen in the line that we can break the line after, leaving room for the
+/// hyphen that's shown. Returns the index just after the soft hyphen.
+fn soft_hyphen_break(line: &str, width: u16, tab_width: usize) -> Option<usize> {
+    line.rmatch_indices(SOFT_HYPHEN)
+        .map(|(i, hyphen)| i + hyphen.len())
+        .find(|&end| text_width(&line[..end], tab_width) < width as usize)
+}
+
+/// Find the last of the characters in the line that it can be broken after, the character stays at
+/// the end of the line so it has to follow part of a word. One at the start of a word, like the
+/// dash of a flag, stays with the word. Returns the index just after the character.
+fn break_after_char(line: &str, break_after: &[char]) -> Option<usize> {
+    line.rmatch_indices(break_after)
+        .find(|&(i, _)| {
+            line[..i]
+                .chars()
+                .next_back()
+                .is_some_and(|c| !c.is_whitespace())
+        })
+        .map(|(i, c)| i + c.len())
+}
+
+/// Wrap the text at word boundaries as `wrap_words` does, but with trailing spaces removed from
+/// each line so that they can be aligned.
+fn wrap_words_trimmed<'t>(
+    text: &'t str,
+    width: u16,
+    tab_width: usize,
+    break_after: &[char],
+) -> Vec<&'t str> {
+    wrap_words(text, width, tab_width, break_after)
+        .into_iter()
+        .map(str::trim_end)
+        .collect()
+}
+
+impl From<&str> for Text {
+    fn from(text: &str) -> Self {
+        Self::new(text.to_string())
+    }
+}
+
+impl From<Vec<String>> for Text {
+    fn from(lines: Vec<String>) -> Self {
+        Self::from_lines(lines)
+    }
+}
+
+impl Text {
+    /// Render the text on a single line with the ellipsis in place of the text that doesn't fit,
+    /// text over several lines never fits so the head comes from the first line and the tail from
+    /// the last. Returns false without rendering anything if the text already fits, or if the
+    /// ellipsis itself doesn't fit in which case the text is just truncated.
+    fn render_with_ellipsis(&self, area: Rect, buf: &mut Buffer) -> bool {
+        let width = area.right() - area.left();
+        let reserved = text_width(&self.ellipsis, self.tab_width()) as u16;
+        let first = split_lines(&self.text).next().unwrap_or(&self.text[..0]);
+        let last = split_lines(&self.text).last().unwrap_or(&self.text[..0]);
+        let fits = split_lines(&self.text).nth(1).is_none()
+            && text_width(first, self.tab_width()) <= width as usize;
+
+        if fits || reserved > width {
+            return false;
+        }
+
+        let available = width - reserved;
+        let head = match self.wrap {
+            TextWrap::TruncateMiddleEllipsis => available - available / 2,
+            TextWrap::TruncateLeftEllipsis => 0,
+            _ => available,
+        };
+        let head_end = byte_index_at_width(first, head as usize, self.tab_width());
+        // A short first line has the ellipsis straight after it, leaving more room for the tail.
+        let head = head.min(text_width(&first[..head_end], self.tab_width()) as u16);
+        let tail = match self.wrap {
+            TextWrap::TruncateMiddleEllipsis | TextWrap::TruncateLeftEllipsis => available - head,
+            _ => 0,
+        };
+        let tail_start = self.offset_of(last)
+            + byte_index_from_end_at_width(last, tail as usize, self.tab_width());
+        let tail_start = tail_start.max(head_end);
+
+        buf.set_line(
+            area.left(),
+            area.top(),
+            &self.styled_line(&self.text[..head_end]),
+            head,
+        );
+        buf.set_stringn(
+            area.left() + head,
+            area.top(),
+            &self.ellipsis,
+            reserved.into(),
+            self.style,
+        );
+        buf.set_line(
+            area.left() + head + reserved,
+            area.top(),
+            &self.styled_line(&self.text[tail_start..]),
+            tail,
+        );
+
+        true
+    }
+
+    /// Render a line that has more text after it that isn't shown, it's truncated if need be to
+    /// fit the ellipsis on the end.
+    fn render_line_with_ellipsis(&self, x: u16, y: u16, line: &Line, width: u16, buf: &mut Buffer) {
+        let reserved = text_width(&self.ellipsis, self.tab_width()) as u16;
+        if reserved > width {
+            buf.set_line(x, y, line, width);
+            return;
+        }
+
+        let (x, y) = buf.set_line(x, y, line, width - reserved);
+        buf.set_stringn(x, y, &self.ellipsis, reserved.into(), self.style);
+    }
+
+    fn render_line_numbers(
+        &self,
+        x: u16,
+        row_of: impl Fn(usize) -> Option<u16>,
+        buf: &mut Buffer,
+        slices: &[&str],
+        gutter: u16,
+    ) {
+        let digits = gutter.saturating_sub(1) as usize;
+
+        self.line_numbers(slices)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(y, number)| Some((row_of(y)?, number)))
+            .for_each(|(row, number)| {
+                let number = number.map(|n| n.to_string()).unwrap_or_default();
+                buf.set_stringn(
+                    x,
+                    row,
+                    format!("{number:>digits$}│"),
+                    gutter.into(),
+                    self.style,
+                );
+            });
+    }
+}
+
+impl WidgetRef for Text {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        tracing::trace!("Rendering text into area {:?}: {:?}", area, self);
+
+        self.render_rows(area, 0, buf);
+    }
+}
+
+impl Text {
+    /// Renders the text as it would be rendered into an area `skip` rows taller than `area`, with
+    /// those rows cut off the top. Only the lines that are shown are rendered.
+    pub(crate) fn render_rows(&self, area: Rect, skip: u16, buf: &mut Buffer) {
+        match self.direction {
+            TextDirection::Ltr => self.render_ltr(area, skip, buf),
+            TextDirection::Rtl => self.render_rtl(area, skip, buf),
+        }
+    }
+
+    // Right to left text is rendered left to right into a scratch buffer which is then mirrored,
+    // so everything that affects where text goes (wrapping, alignment, ellipses) works the same
+    // both ways round. The cells under the text are mirrored into the scratch buffer first so
+    // they end up back where they started.
+    fn render_rtl(&self, area: Rect, skip: u16, buf: &mut Buffer) {
+        // An empty area still has positions, but none of them are in the buffer.
+        if area.is_empty() {
+            return;
+        }
+
+        let gutter = self.gutter_width().min(area.width);
+        let text_area = Rect {
+            x: area.x + gutter,
+            width: area.width - gutter,
+            ..area
+        };
+
+        let mut scratch = Buffer::empty(area);
+        for position in area.positions() {
+            scratch[position] = buf[position].clone();
+        }
+        mirror_cells(buf, &mut scratch, text_area);
+
+        self.render_ltr(area, skip, &mut scratch);
+
+        for position in area.positions() {
+            buf[position] = scratch[position].clone();
+        }
+        mirror_cells(&scratch, buf, text_area);
+    }
+
+    fn render_ltr(&self, area: Rect, skip: u16, buf: &mut Buffer) {
+        let gutter = self.gutter_width().min(area.right() - area.left());
+        let width = area.right() - area.left() - gutter;
+
+        let slices = self.get_line_slices(width);
+
+        let height = area.height as usize + skip as usize;
+        let spare = height.saturating_sub(slices.len());
+        let top = match self.vertical_alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Center => spare / 2,
+            VerticalAlignment::Bottom => spare,
+        };
+        // The row each line is rendered on, if it isn't cut off.
+        let row_of = |y: usize| {
+            let row = (top + y).checked_sub(skip as usize)?;
+            (row < area.height as usize).then(|| area.top() + row as u16)
+        };
+
+        if self.line_numbers {
+            self.render_line_numbers(area.left(), row_of, buf, &slices, gutter);
+        }
+
+        let area = Rect::new(area.left() + gutter, area.top(), width, area.height);
+        let cut_short = self.is_cut_short(&slices);
+        let lines = slices
+            .into_iter()
+            .map(|slice| self.to_line(slice, width))
+            .collect::<Vec<_>>();
+
+        if let TextWrap::TruncateWithEllipsis
+        | TextWrap::TruncateMiddleEllipsis
+        | TextWrap::TruncateLeftEllipsis = self.wrap
+        {
+            // The ellipsis is rendered from the text itself rather than the one truncated line.
+            let Some(row) = row_of(0) else {
+                return;
+            };
+            let line_area = Rect {
+                y: row,
+                height: area.bottom() - row,
+                ..area
+            };
+            if self.render_with_ellipsis(line_area, buf) {
+                return;
+            }
+        }
+
+        let last = lines.len().saturating_sub(1);
+        let overflowed = self.overflow_indicator && lines.len() > height;
+        let last_row = height.saturating_sub(1);
+
+        lines
+            .iter()
+            .enumerate()
+            .filter_map(|(y, line)| Some((y, row_of(y)?, line)))
+            .for_each(|(y, row, line)| {
+                let offset = self.line_offset(line, width);
+                tracing::trace!(
+                    "Rendering line at {} {}: {:?}",
+                    area.left() + offset,
+                    row,
+                    line
+                );
+                let x = area.left() + offset;
+                if (cut_short && y == last) || (overflowed && y == last_row) {
+                    self.render_line_with_ellipsis(x, row, line, width - offset, buf);
+                } else {
+                    buf.set_line(x, row, line, width);
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ratatui::style::{Color, Modifier};
+
+    use tracing_test::traced_test;
+
+    #[traced_test]
+    #[test]
+    fn test_text() {
+        let text = Text::from("Hello, world!");
+        let rect = Rect::new(0, 0, 13, 1);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_text_with_style() {
+        let text = Text::from("Hello, world!")
+            .with_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+        let rect = Rect::new(0, 0, 13, 1);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_words_with_truncate() {
+        let text = Text::new_with_wrap(
+            "Let's not wrap this text even though it's plenty long enough to do so.".to_string(),
+            TextWrap::Truncate,
+        );
+        let rect = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_words_with_truncate_and_ellipsis() {
+        let text = Text::new_with_wrap(
+            "Let's not wrap this text even though it's plenty long enough to do so.".to_string(),
+            TextWrap::TruncateWithEllipsis,
+        );
+        let rect = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_words_with_truncate_and_custom_ellipsis() {
+        let text = Text::new_with_wrap(
+            "Let's not wrap this text even though it's plenty long enough to do so.".to_string(),
+            TextWrap::TruncateWithEllipsis,
+        )
+        .with_ellipsis("…".to_string());
+        let rect = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_truncate_middle_ellipsis() {
+        let text = Text::new_with_wrap(
+            "/a/very/long/path/to/some/file/that/is/deep/down/name.rs".to_string(),
+            TextWrap::TruncateMiddleEllipsis,
+        )
+        .with_ellipsis("…".to_string());
+        let rect = Rect::new(0, 0, 30, 1);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_truncate_left_ellipsis() {
+        let text = Text::new_with_wrap(
+            "/a/very/long/path/to/some/file/that/is/deep/down/name.rs".to_string(),
+            TextWrap::TruncateLeftEllipsis,
+        )
+        .with_ellipsis("…".to_string());
+        let rect = Rect::new(0, 0, 30, 1);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_truncate_ellipsis_when_text_fits() {
+        for wrap in [
+            TextWrap::TruncateWithEllipsis,
+            TextWrap::TruncateMiddleEllipsis,
+            TextWrap::TruncateLeftEllipsis,
+        ] {
+            let text = Text::new_with_wrap("/path/name.rs".to_string(), wrap);
+            let rect = Rect::new(0, 0, 13, 1);
+            let mut buffer = Buffer::empty(rect);
+
+            text.render_ref(rect, &mut buffer);
+
+            assert_eq!(buffer, Buffer::with_lines(["/path/name.rs"]));
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_truncate_ellipsis_narrow() {
+        for wrap in [
+            TextWrap::TruncateWithEllipsis,
+            TextWrap::TruncateMiddleEllipsis,
+            TextWrap::TruncateLeftEllipsis,
+        ] {
+            let text = Text::new_with_wrap("/path/name.rs".to_string(), wrap);
+            let render = |width| {
+                let rect = Rect::new(0, 0, width, 1);
+                let mut buffer = Buffer::empty(rect);
+                text.render_ref(rect, &mut buffer);
+                buffer
+            };
+
+            // Too narrow for the ellipsis the text is just truncated.
+            assert_eq!(render(0), Buffer::empty(Rect::new(0, 0, 0, 1)));
+            assert_eq!(render(1), Buffer::with_lines(["/"]));
+            assert_eq!(render(2), Buffer::with_lines(["/p"]));
+            assert_eq!(render(3), Buffer::with_lines(["..."]));
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_truncate_ellipsis_on_overflow() {
+        let render = |text: &str, wrap| {
+            let text = Text::new_with_wrap(text.to_string(), wrap);
+            let rect = Rect::new(0, 0, 10, 1);
+            let mut buffer = Buffer::empty(rect);
+            text.render_ref(rect, &mut buffer);
+            buffer
+        };
+
+        let wrap = || TextWrap::TruncateWithEllipsis;
+        assert_eq!(render("Short", wrap()), Buffer::with_lines(["Short     "]));
+        assert_eq!(
+            render("Exactly 10", wrap()),
+            Buffer::with_lines(["Exactly 10"])
+        );
+        assert_eq!(
+            render("Longer than 10", wrap()),
+            Buffer::with_lines(["Longer ..."])
+        );
+        assert_eq!(
+            render("Two\nlines", wrap()),
+            Buffer::with_lines(["Two...    "])
+        );
+        assert_eq!(
+            render("Trailing\n", wrap()),
+            Buffer::with_lines(["Trailing  "])
+        );
+        assert_eq!(
+            render("Two\nlines", TextWrap::TruncateMiddleEllipsis),
+            Buffer::with_lines(["Two...ines"])
+        );
+        assert_eq!(
+            render("Two\nlines", TextWrap::TruncateLeftEllipsis),
+            Buffer::with_lines(["...lines  "])
+        );
+        assert_eq!(
+            render("Two\nlines", TextWrap::Truncate),
+            Buffer::with_lines(["Two       "])
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_with_wrap() {
+        let text = Text::new_with_wrap(
+            "Let's wrap this text that is long enough to do so.".to_string(),
+            TextWrap::Wrapped,
+        );
+        let rect = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_with_wrap_and_newlines() {
+        let text = Text::new_with_wrap(
+            "Let's wrap this text that is long enough to do so.\nAnd it has a newline.".to_string(),
+            TextWrap::Wrapped,
+        );
+        let rect = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_with_wrap_justified() {
+        let text = Text::new_with_wrap(
+            "Let's justify this text that is long enough to wrap over a few lines.\nNew paragraph."
+                .to_string(),
+            TextWrap::WrappedJustified,
+        );
+        let rect = Rect::new(0, 0, 30, 4);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_with_wrap_centered() {
+        let text = Text::new_with_wrap(
+            "Let's centre this text.\naaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string(),
+            TextWrap::WrappedCentered,
+        );
+        let rect = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_with_wrap_right_aligned() {
+        let text = Text::new_with_wrap(
+            "Let's right align this text that is long enough to wrap.   \naaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+                .to_string(),
+            TextWrap::WrappedRightAligned,
+        );
+        let rect = Rect::new(0, 0, 40, 4);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_long_text_word_wrap() {
+        let text = Text::from("String that is longer than the 40 characters of the rectangle.");
+        let rect = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_long_words_text_word_wrap() {
+        let text = Text::from("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+        let rect = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_word_wrap_last_word_straddles_width() {
+        let text = Text::from("héllo wörld café");
+
+        assert_eq!(
+            text.wrapped_lines(14).collect::<Vec<_>>(),
+            vec!["héllo wörld", "café"]
+        );
+
+        // Whatever the width the lines hold every character apart from the spaces wrapped at.
+        let letters = |s: &str| s.chars().filter(|c| *c != ' ').collect::<String>();
+        for width in 1..=20 {
+            let lines = text.wrapped_lines(width).collect::<Vec<_>>();
+            assert_eq!(letters(&lines.concat()), letters("héllo wörld café"));
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_word_wrap_long_input() {
+        let text = Text::from("word ".repeat(10_000).as_str());
+        let start = std::time::Instant::now();
+
+        let lines = text.wrapped_lines(80).collect::<Vec<_>>();
+
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+        assert_eq!(lines.len(), 625);
+        assert!(lines.iter().all(|line| *line == vec!["word"; 16].join(" ")));
+        assert_eq!(text.get_height(80), 625);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_with_word_wrap_and_newlines() {
+        let text =
+            Text::from("Let's wrap this text that is long enough to do so.\nAnd it has a newline.");
+        let rect = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_multibyte_text_wrap() {
+        let text = Text::new_with_wrap("héllo wörld café".to_string(), TextWrap::Wrapped);
+        let rect = Rect::new(0, 0, 4, 4);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_multibyte_text_any_width() {
+        let wraps = [
+            TextWrap::Truncate,
+            TextWrap::TruncateWord,
+            TextWrap::TruncateWithEllipsis,
+            TextWrap::TruncateMiddleEllipsis,
+            TextWrap::TruncateLeftEllipsis,
+            TextWrap::Wrapped,
+            TextWrap::WrappedWords,
+            TextWrap::WrappedJustified,
+            TextWrap::WrappedCentered,
+            TextWrap::WrappedRightAligned,
+        ];
+
+        for wrap in wraps {
+            let text = Text::new_with_wrap("héllo wörld café".to_string(), wrap);
+            for width in 1..=16 {
+                let rect = Rect::new(0, 0, width, 16);
+                let mut buffer = Buffer::empty(rect);
+
+                text.render_ref(rect, &mut buffer);
+            }
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_wide_text_wrap() {
+        let text = Text::from("日本語テキストの折り返し");
+        let rect = Rect::new(0, 0, 10, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_grapheme_cluster_wrap() {
+        let family = "👩\u{200d}👩\u{200d}👧\u{200d}👦";
+        let text = Text::new_with_wrap(format!("ab{family}e\u{301}"), TextWrap::Wrapped);
+        let rect = Rect::new(0, 0, 4, 2);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        assert_eq!(buffer[(2, 0)].symbol(), family);
+        assert_eq!(buffer[(0, 1)].symbol(), "e\u{301}");
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_spans_wrap() {
+        let text = Text::from_spans(vec![
+            Span::raw("Some plain text then "),
+            Span::styled("some red text that wraps", Style::default().fg(Color::Red)),
+            Span::raw(" and more plain text."),
+        ]);
+        let rect = Rect::new(0, 0, 30, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_line_numbers() {
+        let text = Text::from(
+            "Let's number these lines.\nThis one is long enough that it wraps over a few lines.\nAnd a last line.",
+        )
+        .with_line_numbers(true);
+        let rect = Rect::new(0, 0, 30, 5);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_tab_expansion() {
+        let text = Text::from("a\tb\tcc\td\n日\te");
+        let rect = Rect::new(0, 0, 20, 2);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_tab_expansion_with_tab_width() {
+        let text = Text::from("a\tb\tcc\td").with_tab_width(8);
+        let rect = Rect::new(0, 0, 30, 1);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_tab_stops_line_up() {
+        let text = Text::from("a\tb\tc\nab\tbc\tc");
+        let rect = Rect::new(0, 0, 10, 2);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        assert_eq!(buffer, Buffer::with_lines(["a   b   c ", "ab  bc  c "]));
+        assert_eq!(
+            text.wrapped_lines(10).collect::<Vec<_>>(),
+            vec!["a   b   c", "ab  bc  c"]
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_max_lines() {
+        let text = Text::from(
+            "One line. Two lines. Three lines. Four lines. Five lines. Six lines. Seven lines. \
+             Eight lines. Nine lines. Ten lines.",
+        )
+        .with_max_lines(3);
+        let rect = Rect::new(0, 0, 12, 5);
+        let mut buffer = Buffer::empty(rect);
+
+        assert_eq!(text.get_height(12), 3);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_overflow_indicator() {
+        let text = Text::from("One line. Two lines. Three lines. Four lines. Five lines.");
+        let rect = Rect::new(0, 0, 13, 3);
+        assert_eq!(text.get_height(rect.width), 5);
+
+        let mut buffer = Buffer::empty(rect);
+        text.render_ref(rect, &mut buffer);
+        assert_eq!(
+            buffer,
+            Buffer::with_lines(vec!["One line. Two", "lines. Three ", "lines. Four  "])
+        );
+
+        let text = text.overflow_indicator(true);
+        let mut buffer = Buffer::empty(rect);
+        text.render_ref(rect, &mut buffer);
+        assert_eq!(
+            buffer,
+            Buffer::with_lines(vec!["One line. Two", "lines. Three ", "lines. Fou..."])
+        );
+
+        // Nothing is shown when everything fits.
+        let rect = Rect::new(0, 0, 13, 5);
+        let mut buffer = Buffer::empty(rect);
+        text.render_ref(rect, &mut buffer);
+        let mut expected = Buffer::empty(rect);
+        text.overflow_indicator(false)
+            .render_ref(rect, &mut expected);
+        assert_eq!(buffer, expected);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_max_lines_zero_is_unlimited() {
+        let text = Text::from("One line. Two lines. Three lines.").with_max_lines(0);
+
+        assert_eq!(text.get_height(12), 3);
+    }
+
+    fn render_with_vertical_alignment(alignment: VerticalAlignment) -> Buffer {
+        let text = Text::from("Let's align this text that wraps over two lines.")
+            .with_vertical_alignment(alignment);
+        let rect = Rect::new(0, 0, 40, 5);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        buffer
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_vertical_alignment_top() {
+        insta::assert_debug_snapshot!(render_with_vertical_alignment(VerticalAlignment::Top));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_vertical_alignment_center() {
+        insta::assert_debug_snapshot!(render_with_vertical_alignment(VerticalAlignment::Center));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_vertical_alignment_bottom() {
+        insta::assert_debug_snapshot!(render_with_vertical_alignment(VerticalAlignment::Bottom));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_with_wrap_and_mixed_line_breaks() {
+        let text = Text::new_with_wrap(
+            "Windows line.\r\nUnix line.\nOld Mac line.\rLast line.".to_string(),
+            TextWrap::Wrapped,
+        );
+        let rect = Rect::new(0, 0, 20, 4);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_with_word_wrap_and_mixed_line_breaks() {
+        let text = Text::from("Windows line.\r\nUnix line.\nOld Mac line.\rLast line.");
+        let rect = Rect::new(0, 0, 20, 4);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_lines_cached_per_width() {
+        let text = Text::from("Let's wrap this text that is long enough to do so.");
+        let rect = Rect::new(0, 0, 20, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        assert_eq!(text.get_height(20), 3);
+        text.render_ref(rect, &mut buffer);
+        assert_eq!(text.get_height(30), 2);
+        assert_eq!(text.get_height(30), 2);
+
+        logs_assert(|lines| {
+            let wraps = lines
+                .iter()
+                .filter(|line| line.contains("Getting lines at width"))
+                .count();
+            match wraps {
+                2 => Ok(()),
+                n => Err(format!(
+                    "Expected text to be wrapped twice, was wrapped {n} times"
+                )),
+            }
+        });
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_line_count_matches_rendered_rows() {
+        let text = Text::from("Let's wrap this text that is long enough to take a few lines.");
+        let rect = Rect::new(0, 0, 12, 10);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        let rows = (0..rect.height)
+            .filter(|&y| (0..rect.width).any(|x| buffer[(x, y)].symbol() != " "))
+            .count();
+        assert_eq!(text.line_count(12), 6);
+        assert_eq!(text.line_count(12) as usize, rows);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_wrapped_lines() {
+        let text = Text::from("Let's wrap this text that is long enough.");
+
+        assert_eq!(
+            text.wrapped_lines(20).collect::<Vec<_>>(),
+            vec!["Let's wrap this text", "that is long enough."]
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_wrapped_lines_justified() {
+        let text = Text::new_with_wrap(
+            "Let's justify this text that is long enough.".to_string(),
+            TextWrap::WrappedJustified,
+        )
+        .with_line_numbers(true);
+
+        assert_eq!(
+            text.wrapped_lines(22).collect::<Vec<_>>(),
+            vec!["Let's  justify  this", "text  that  is  long", "enough."]
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_builder() {
+        let text =
+            Text::new_builder("Let's build this text that is long enough to wrap.".to_string())
+                .wrap(TextWrap::WrappedCentered)
+                .style(Style::default().fg(Color::Red))
+                .line_numbers(true)
+                .build();
+        let rect = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_builder_from_default() {
+        let text = TextBuilder::default()
+            .text("Some styled text\nthat wraps over the width".to_string())
+            .wrap(TextWrap::WrappedWords)
+            .style(Style::default().fg(Color::Green))
+            .line_numbers(true)
+            .build();
+        let rect = Rect::new(0, 0, 20, 4);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_word_wrap_soft_hyphens() {
+        let text = Text::from(
+            "pneu\u{ad}mono\u{ad}ultra\u{ad}micro\u{ad}scopic\u{ad}silico\u{ad}volcano\u{ad}coniosis",
+        );
+        let rect = Rect::new(0, 0, 15, 5);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_highlight() {
+        let text = Text::from("Hello, world! hello WORLD")
+            .with_highlight("world".to_string(), Style::default().bg(Color::Yellow));
+        let rect = Rect::new(0, 0, 30, 1);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_highlight_over_wrap() {
+        let text = Text::new_with_wrap("Hello, world! hello WORLD".to_string(), TextWrap::Wrapped)
+            .with_highlight("world".to_string(), Style::default().bg(Color::Yellow));
+        let rect = Rect::new(0, 0, 10, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_from_ansi() {
+        let text = Text::from_ansi("\x1b[31mred\x1b[0m normal \x1b[1;44mbold on blue\x1b[0m");
+        let rect = Rect::new(0, 0, 30, 1);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_blank_lines_preserved() {
+        let wraps = [
+            TextWrap::Wrapped,
+            TextWrap::WrappedWords,
+            TextWrap::WrappedJustified,
+            TextWrap::WrappedCentered,
+            TextWrap::WrappedRightAligned,
+        ];
+
+        for wrap in wraps {
+            let text =
+                Text::new_with_wrap("Para one.\n\nPara two.\n\n\nPara three".to_string(), wrap);
+
+            assert_eq!(
+                text.wrapped_lines(20).collect::<Vec<_>>(),
+                vec!["Para one.", "", "Para two.", "", "", "Para three"]
+            );
+            assert_eq!(text.get_height(20), 6);
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_blank_lines_after_full_width_line() {
+        let text = Text::new_with_wrap("abcd\n\nefgh\nijkl mnop".to_string(), TextWrap::Wrapped);
+
+        assert_eq!(
+            text.wrapped_lines(4).collect::<Vec<_>>(),
+            vec!["abcd", "", "efgh", "ijkl", " mno", "p"]
+        );
+
+        let text = Text::from("abcd\n\nefgh\nijkl mnop");
+
+        assert_eq!(
+            text.wrapped_lines(4).collect::<Vec<_>>(),
+            vec!["abcd", "", "efgh", "ijkl", "mnop"]
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_trim_trailing() {
+        let text = Text::from("  Some text    with lots    of spaces")
+            .with_style(Style::default().bg(Color::Blue));
+        let rect = Rect::new(0, 0, 12, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_no_trim_trailing() {
+        let text = Text::from("  Some text    with lots    of spaces").with_trim_trailing(false);
+
+        assert_eq!(
+            text.wrapped_lines(12).collect::<Vec<_>>(),
+            vec!["  Some text ", "with lots   ", "of spaces"]
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_height_saturates() {
+        let text = Text::from("a\n".repeat(70_000).as_str());
+
+        assert_eq!(text.get_height(10), u16::MAX);
+    }
+
+    // TODO: Test with newlines and wrapping
+
+    #[traced_test]
+    #[test]
+    fn test_measure_single_line() {
+        let text = Text::from("Hello world");
+        assert_eq!(text.measure(20), (11, 1));
+
+        let text = Text::new_with_wrap("Hello world".to_string(), TextWrap::Truncate);
+        assert_eq!(text.measure(8), (8, 1));
+
+        assert_eq!(Text::from("").measure(20), (0, 0));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_measure_multi_line() {
+        let text = Text::from("A line that wraps\nand a short one");
+        assert_eq!(text.measure(12), (11, 4));
+
+        let text = Text::from("One\nTwo\nThree").with_line_numbers(true);
+        assert_eq!(text.measure(20), (7, 3));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_rtl() {
+        let text = Text::from("שלום עולם, מה שלומך היום?").with_direction(TextDirection::Rtl);
+        let rect = Rect::new(0, 0, 14, 3);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
     }
 
-    // Possibly consider memoizing this, we effectively do the same work twice for panels, though
-    // it should be pretty fast I think. Maybe we should profile it at some point for different
-    // wrappings.
-    fn get_lines(&'a self, width: u16) -> Vec<&'a str> {
-        tracing::trace!("Getting lines at width {} for text: {:?}", width, self);
+    #[traced_test]
+    #[test]
+    fn test_rtl_wide_characters() {
+        let text = Text::from("日本 語").with_direction(TextDirection::Rtl);
+        let rect = Rect::new(0, 0, 8, 1);
+        let mut buffer = Buffer::empty(rect);
 
-        match self.wrap {
-            TextWrap::Truncate => self.get_lines_truncate(width),
-            TextWrap::TruncateWithEllipsis => self.get_lines_truncate(width), // Ellipsis handlded by the renderer
-            TextWrap::Wrapped => self.get_lines_wrapped(width),
-            TextWrap::WrappedWords => self.get_lines_wrapped_words(width),
-            TextWrap::WrappedJustified => self.get_lines_wrapped_justified(width),
-            TextWrap::WrappedCentered => self.get_lines_wrapped_centered(width),
-            TextWrap::WrappedRightAligned => self.get_lines_wrapped_right_aligned(width),
-        }
-    }
+        text.render_ref(rect, &mut buffer);
 
-    fn get_lines_truncate(&'a self, width: u16) -> Vec<&'a str> {
-        let end = self.text.len().min(width as usize);
-        vec![&self.text[..end]]
+        assert_eq!(buffer, Buffer::with_lines([" 語 本日"]));
     }
 
-    fn get_lines_wrapped(&'a self, width: u16) -> Vec<&'a str> {
-        let mut pos = 0;
-        let mut lines = vec![];
+    #[traced_test]
+    #[test]
+    fn test_rtl_narrow() {
+        let text = Text::from("שלום")
+            .with_direction(TextDirection::Rtl)
+            .with_line_numbers(true);
+        // Like left to right text, nothing is shown until there's room for some of the text.
+        let expected = ["", " ", "  ", "1│ש", "1│לש"];
 
-        while pos <= self.text.len() {
-            let end = pos + width as usize;
-            let end = end.min(self.text.len());
-            let line = &self.text[pos..end];
-            if line.is_empty() {
-                break;
-            }
+        for (width, expected) in expected.into_iter().enumerate() {
+            let rect = Rect::new(0, 0, width as u16, 1);
+            let mut buffer = Buffer::empty(rect);
 
-            if let Some(to) = line.find('\n') {
-                lines.push(&line[..to]);
-                pos += to + 1;
-                continue;
-            }
+            text.render_ref(rect, &mut buffer);
 
-            lines.push(line);
+            assert_eq!(buffer, Buffer::with_lines([expected]), "width {width}");
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_truncate_word() {
+        let sentence = "Let's not wrap this text at all";
+        let rect = Rect::new(0, 0, 17, 4);
+        let mut buffer = Buffer::empty(rect);
 
-            pos = end;
+        // Truncate on the first row to compare against.
+        let rows = [
+            (TextWrap::Truncate, sentence),
+            (TextWrap::TruncateWord, sentence),
+            (TextWrap::TruncateWord, "Incomprehensibilities"),
+            (TextWrap::TruncateWord, "Short"),
+        ];
+        for (y, (wrap, sample)) in rows.into_iter().enumerate() {
+            Text::new_with_wrap(sample.to_string(), wrap)
+                .render_ref(Rect::new(0, y as u16, 17, 1), &mut buffer);
         }
 
-        tracing::trace!("Lines: {:?}", lines);
+        insta::assert_debug_snapshot!(buffer);
+    }
 
-        lines
+    #[traced_test]
+    #[test]
+    fn test_justified_paragraph_ends_left_aligned() {
+        // The last line of each paragraph nearly fills the width but isn't stretched to fill it.
+        let text = Text::new_with_wrap(
+            "One two three four five six\nSeven eight nine".to_string(),
+            TextWrap::WrappedJustified,
+        );
+        let rect = Rect::new(0, 0, 15, 4);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
     }
 
-    fn get_lines_wrapped_words(&'a self, width: u16) -> Vec<&'a str> {
-        let mut pos = 0;
-        let mut lines = vec![];
+    #[traced_test]
+    #[test]
+    fn test_centered_paragraph() {
+        let text = Text::new_with_wrap(
+            "Let's centre a paragraph of text that's long enough to wrap onto several lines at this width."
+                .to_string(),
+            TextWrap::WrappedCentered,
+        );
+        let rect = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(rect);
 
-        while pos <= self.text.len() {
-            let end = pos + width as usize;
-            let end = end.min(self.text.len());
-            let line = &self.text[pos..end];
-            if line.is_empty() {
-                break;
-            }
+        text.render_ref(rect, &mut buffer);
 
-            let to = if self.text.len() == end || self.text[end..].starts_with(' ') {
-                end
-            } else {
-                line.rfind(' ').map(|y| pos + y).unwrap_or(end)
-            };
+        insta::assert_debug_snapshot!(buffer);
+    }
 
-            if let Some(to) = line.find('\n') {
-                lines.push(&line[..to]);
-                pos += to + 1;
-                continue;
-            }
+    #[traced_test]
+    #[test]
+    fn test_right_aligned_long_word() {
+        let text = Text::new_with_wrap(
+            "Incomprehensibilities abound".to_string(),
+            TextWrap::WrappedRightAligned,
+        );
+        let rect = Rect::new(0, 0, 10, 4);
+        let mut buffer = Buffer::empty(rect);
 
-            lines.push(&self.text[pos..to]);
+        text.render_ref(rect, &mut buffer);
 
-            pos = to;
+        insta::assert_debug_snapshot!(buffer);
+    }
 
-            while pos < self.text.len() && self.text.chars().nth(pos) == Some(' ') {
-                pos += 1;
-            }
+    #[traced_test]
+    #[test]
+    fn test_cjk_and_emoji_narrow() {
+        let sample = "日本語テキスト 👍🏽👨‍👩‍👧 ok";
+        let wraps = [
+            TextWrap::Truncate,
+            TextWrap::TruncateWord,
+            TextWrap::TruncateWithEllipsis,
+            TextWrap::TruncateMiddleEllipsis,
+            TextWrap::TruncateLeftEllipsis,
+            TextWrap::Wrapped,
+            TextWrap::WrappedWords,
+            TextWrap::WrappedJustified,
+            TextWrap::WrappedCentered,
+            TextWrap::WrappedRightAligned,
+        ];
+        let rect = Rect::new(0, 0, 5, 8);
+
+        for wrap in wraps {
+            let mut buffer = Buffer::empty(rect);
+            Text::new_with_wrap(sample.to_string(), wrap).render_ref(rect, &mut buffer);
         }
 
-        tracing::trace!("Lines: {:?}", lines);
+        let mut buffer = Buffer::empty(rect);
+        Text::from(sample).render_ref(rect, &mut buffer);
 
-        lines
+        insta::assert_debug_snapshot!(buffer);
     }
 
-    fn get_lines_wrapped_justified(&'a self, width: u16) -> Vec<&'a str> {
-        todo!();
-    }
+    #[traced_test]
+    #[test]
+    fn test_wide_text_measured_in_columns() {
+        let text = Text::from("你好世界");
 
-    fn get_lines_wrapped_centered(&'a self, width: u16) -> Vec<&'a str> {
-        todo!();
+        assert_eq!(text.get_width(), 8);
+        assert_eq!(text.measure(20), (8, 1));
+        // Only two of the characters fit in 5 columns.
+        assert_eq!(text.measure(5), (4, 2));
+        assert_eq!(text.get_height(5), 2);
     }
 
-    fn get_lines_wrapped_right_aligned(&'a self, width: u16) -> Vec<&'a str> {
-        todo!();
+    #[traced_test]
+    #[test]
+    fn test_style_applied_to_cells() {
+        let text = Text::from("Hello").style(Style::new().fg(Color::Green).bg(Color::Black));
+        let rect = Rect::new(0, 0, 8, 1);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        for x in 0..5 {
+            assert_eq!(buffer[(x, 0)].fg, Color::Green);
+            assert_eq!(buffer[(x, 0)].bg, Color::Black);
+        }
+        assert_eq!(buffer[(5, 0)].fg, Color::Reset);
     }
-}
 
-impl From<&str> for Text {
-    fn from(text: &str) -> Self {
-        Self::new(text.to_string())
+    #[traced_test]
+    #[test]
+    fn test_line_numbers_narrow() {
+        let text = Text::from(
+            "Let's number these lines.\nThis one is long enough that it wraps over a few lines.\nAnd a last line.",
+        )
+        .with_line_numbers(true);
+        let rect = Rect::new(0, 0, 16, 9);
+        let mut buffer = Buffer::empty(rect);
+
+        text.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
     }
-}
 
-impl WidgetRef for Text {
-    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
-        tracing::trace!("Rendering text into area {:?}: {:?}", area, self);
+    #[traced_test]
+    #[test]
+    fn test_line_numbers_three_digits() {
+        let lines = (1..=120).map(|i| format!("Line {i}")).collect::<Vec<_>>();
+        let text = Text::new(lines.join("\n")).with_line_numbers(true);
+        let rect = Rect::new(0, 0, 12, 3);
+        let mut buffer = Buffer::empty(rect);
 
-        let width = area.right() - area.left();
+        text.render_ref(rect, &mut buffer);
 
-        let lines = self.get_lines(width);
+        assert_eq!(text.gutter_width(), 4);
+        assert_eq!(
+            buffer,
+            Buffer::with_lines(["  1│Line 1  ", "  2│Line 2  ", "  3│Line 3  "])
+        );
+    }
 
-        if let TextWrap::TruncateWithEllipsis = self.wrap {
-            debug_assert!(lines.len() == 1);
-            if lines[0].len() == width as usize {
-                buf.set_stringn(
-                    area.left(),
-                    area.top(),
-                    &lines[0][..(width as usize - 3)],
-                    width.into(),
-                    Style::default(),
-                );
-                buf.set_stringn(
-                    area.left() + width - 3,
-                    area.top(),
-                    "...",
-                    3,
-                    Style::default(),
-                );
-                return;
-            }
-        }
+    #[traced_test]
+    #[test]
+    fn test_lines_cache_invalidated() {
+        let mut text = Text::from("Let's wrap this text that is long enough to do so.");
 
-        lines
-            .iter()
-            .enumerate()
-            .filter(|(y, _)| y + (area.top() as usize) < (area.bottom() as usize))
-            .for_each(|(y, line)| {
-                tracing::trace!(
-                    "Rendering line at {} {}: {:?}",
-                    area.left(),
-                    area.top() + y as u16,
-                    line
-                );
-                buf.set_stringn(
-                    area.left(),
-                    area.top() + y as u16,
-                    line,
-                    width.into(),
-                    Style::default(),
-                );
-            });
+        assert_eq!(text.get_height(20), 3);
+        assert_eq!(text.get_height(20), 3);
+        text.set_text("Short".to_string());
+        assert_eq!(text.get_height(20), 1);
+        text.set_wrap(TextWrap::Wrapped);
+        assert_eq!(text.get_height(20), 1);
+
+        logs_assert(|lines| {
+            let wraps = lines
+                .iter()
+                .filter(|line| line.contains("Getting lines at width"))
+                .count();
+            match wraps {
+                3 => Ok(()),
+                n => Err(format!(
+                    "Expected text to be wrapped three times, was wrapped {n} times"
+                )),
+            }
+        });
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[traced_test]
+    #[test]
+    fn test_truncate_alignment() {
+        let render = |alignment: Alignment, text: &str| {
+            let text =
+                Text::new_with_wrap(text.to_string(), TextWrap::Truncate).with_alignment(alignment);
+            let rect = Rect::new(0, 0, 20, 1);
+            let mut buffer = Buffer::empty(rect);
+            text.render_ref(rect, &mut buffer);
+            buffer
+        };
 
-    use tracing_test::traced_test;
+        assert_eq!(
+            render(Alignment::Left, "Header"),
+            Buffer::with_lines(["Header              "])
+        );
+        assert_eq!(
+            render(Alignment::Center, "Header"),
+            Buffer::with_lines(["       Header       "])
+        );
+        assert_eq!(
+            render(Alignment::Right, "Header"),
+            Buffer::with_lines(["              Header"])
+        );
+        assert_eq!(
+            render(Alignment::Right, "A header of twenty c"),
+            Buffer::with_lines(["A header of twenty c"])
+        );
+    }
 
     #[traced_test]
     #[test]
-    fn test_text() {
-        let text = Text::from("Hello, world!");
-        let rect = Rect::new(0, 0, 13, 1);
+    fn test_truncated_with_ellipsis_alignment_when_full() {
+        let text = Text::new_builder("A header that is much too long".to_string())
+            .wrap(TextWrap::TruncateWithEllipsis)
+            .alignment(Alignment::Center)
+            .build();
+        let rect = Rect::new(0, 0, 20, 1);
         let mut buffer = Buffer::empty(rect);
 
         text.render_ref(rect, &mut buffer);
 
-        insta::assert_debug_snapshot!(buffer);
+        assert_eq!(buffer, Buffer::with_lines(["A header that is ..."]));
     }
 
-    // TODO: Styles
-
     #[traced_test]
     #[test]
-    fn test_words_with_truncate() {
-        let text = Text::new_with_wrap(
-            "Let's not wrap this text even though it's plenty long enough to do so.".to_string(),
-            TextWrap::Truncate,
+    fn test_blank_lines_between_paragraphs() {
+        let text = Text::from("para1\r\n\r\npara2");
+
+        assert_eq!(
+            text.wrapped_lines(10).collect::<Vec<_>>(),
+            vec!["para1", "", "para2"]
         );
-        let rect = Rect::new(0, 0, 40, 3);
+
+        let text = Text::from("para1\n\npara2");
+        let rect = Rect::new(0, 0, 5, 4);
         let mut buffer = Buffer::empty(rect);
 
         text.render_ref(rect, &mut buffer);
 
-        insta::assert_debug_snapshot!(buffer);
+        assert_eq!(
+            buffer,
+            Buffer::with_lines(["para1", "     ", "para2", "     "])
+        );
     }
 
     #[traced_test]
     #[test]
-    fn test_words_with_truncate_and_ellipsis() {
-        let text = Text::new_with_wrap(
-            "Let's not wrap this text even though it's plenty long enough to do so.".to_string(),
-            TextWrap::TruncateWithEllipsis,
-        );
-        let rect = Rect::new(0, 0, 40, 3);
+    fn test_trailing_newline() {
+        let truncating = || {
+            [
+                TextWrap::Truncate,
+                TextWrap::TruncateWord,
+                TextWrap::TruncateWithEllipsis,
+                TextWrap::TruncateMiddleEllipsis,
+                TextWrap::TruncateLeftEllipsis,
+            ]
+        };
+        let wrapping = || {
+            [
+                TextWrap::Wrapped,
+                TextWrap::WrappedWords,
+                TextWrap::WrappedJustified,
+                TextWrap::WrappedCentered,
+                TextWrap::WrappedRightAligned,
+            ]
+        };
+
+        // A single trailing line break ends the line rather than starting another.
+        for wrap in truncating().into_iter().chain(wrapping()) {
+            let text = Text::new_with_wrap("abc\n".to_string(), wrap);
+            assert_eq!(text.wrapped_lines(10).collect::<Vec<_>>(), vec!["abc"]);
+            assert_eq!(text.get_height(10), 1);
+        }
+
+        // A second one is an explicit blank line, though truncated text only shows its first line.
+        for wrap in truncating() {
+            let text = Text::new_with_wrap("abc\n\n".to_string(), wrap);
+            assert_eq!(text.wrapped_lines(10).collect::<Vec<_>>(), vec!["abc"]);
+            assert_eq!(text.get_height(10), 1);
+        }
+        for wrap in wrapping() {
+            let text = Text::new_with_wrap("abc\n\n".to_string(), wrap);
+            assert_eq!(text.wrapped_lines(10).collect::<Vec<_>>(), vec!["abc", ""]);
+            assert_eq!(text.get_height(10), 2);
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_from_lines() {
+        let text = Text::from_lines(vec!["a".to_string(), "b".to_string()]);
+        let rect = Rect::new(0, 0, 3, 3);
         let mut buffer = Buffer::empty(rect);
 
         text.render_ref(rect, &mut buffer);
 
-        insta::assert_debug_snapshot!(buffer);
+        assert_eq!(buffer, Buffer::with_lines(["a  ", "b  ", "   "]));
+
+        let text = Text::from(vec!["one".to_string(), String::new(), "three".to_string()]);
+        assert_eq!(
+            text.wrapped_lines(10).collect::<Vec<_>>(),
+            vec!["one", "", "three"]
+        );
     }
 
     #[traced_test]
     #[test]
-    fn test_with_wrap() {
-        let text = Text::new_with_wrap(
-            "Let's wrap this text that is long enough to do so.".to_string(),
-            TextWrap::Wrapped,
+    fn test_word_wrap_breaks_after_hyphens() {
+        let text = Text::from("this-is-a-very-long-hyphenated-identifier");
+
+        assert_eq!(
+            text.wrapped_lines(12).collect::<Vec<_>>(),
+            vec!["this-is-a-", "very-long-", "hyphenated-", "identifier"]
         );
-        let rect = Rect::new(0, 0, 40, 3);
-        let mut buffer = Buffer::empty(rect);
 
-        text.render_ref(rect, &mut buffer);
+        let text = Text::from("see src/widgets/text.rs");
+        assert_eq!(
+            text.wrapped_lines(14).collect::<Vec<_>>(),
+            vec!["see src/", "widgets/", "text.rs"]
+        );
 
-        insta::assert_debug_snapshot!(buffer);
+        let text = Text::from("-leading -x");
+        assert_eq!(
+            text.wrapped_lines(10).collect::<Vec<_>>(),
+            vec!["-leading", "-x"]
+        );
+
+        let text = Text::from("this-is-a-very-long-hyphenated-identifier").with_break_after(vec![]);
+        assert_eq!(
+            text.wrapped_lines(12).collect::<Vec<_>>(),
+            vec!["this-is-a-ve", "ry-long-hyph", "enated-ident", "ifier"]
+        );
     }
 
     #[traced_test]
     #[test]
-    fn test_with_wrap_and_newlines() {
+    fn test_preformatted_clip() {
         let text = Text::new_with_wrap(
-            "Let's wrap this text that is long enough to do so.\nAnd it has a newline.".to_string(),
-            TextWrap::Wrapped,
+            "fn main() {\n    println!(\"Hello, world!\");\n}".to_string(),
+            TextWrap::PreformattedClip,
         );
-        let rect = Rect::new(0, 0, 40, 3);
+        let rect = Rect::new(0, 0, 16, 4);
         let mut buffer = Buffer::empty(rect);
 
         text.render_ref(rect, &mut buffer);
 
-        insta::assert_debug_snapshot!(buffer);
+        assert_eq!(text.get_height(16), 3);
+        assert_eq!(
+            buffer,
+            Buffer::with_lines([
+                "fn main() {     ",
+                "    println!(\"He",
+                "}               ",
+                "                ",
+            ])
+        );
     }
 
     #[traced_test]
     #[test]
-    fn test_long_text_word_wrap() {
-        let text = Text::from("String that is longer than the 40 characters of the rectangle.");
-        let rect = Rect::new(0, 0, 40, 3);
+    fn test_truncate_ellipsis_with_embedded_newline() {
+        let text = Text::new_with_wrap("line1\nline2".to_string(), TextWrap::TruncateWithEllipsis);
+        let rect = Rect::new(0, 0, 20, 2);
         let mut buffer = Buffer::empty(rect);
 
         text.render_ref(rect, &mut buffer);
 
-        insta::assert_debug_snapshot!(buffer);
+        assert_eq!(text.get_height(20), 1);
+        assert_eq!(
+            buffer,
+            Buffer::with_lines(["line1...            ", "                    "])
+        );
     }
 
     #[traced_test]
     #[test]
-    fn test_long_words_text_word_wrap() {
-        let text = Text::from("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
-        let rect = Rect::new(0, 0, 40, 3);
-        let mut buffer = Buffer::empty(rect);
+    fn test_truncate_empty_text_with_capacity() {
+        // An empty string with capacity doesn't point at a static empty string, so lines that
+        // aren't slices of it can't be located within it.
+        for wrap in [
+            TextWrap::Truncate,
+            TextWrap::TruncateWithEllipsis,
+            TextWrap::TruncateMiddleEllipsis,
+            TextWrap::TruncateLeftEllipsis,
+            TextWrap::TruncateWord,
+        ] {
+            let text = Text::new_with_wrap(String::with_capacity(8), wrap);
+            let rect = Rect::new(0, 0, 10, 1);
+            let mut buffer = Buffer::empty(rect);
 
-        text.render_ref(rect, &mut buffer);
+            text.render_ref(rect, &mut buffer);
 
-        insta::assert_debug_snapshot!(buffer);
+            assert_eq!(text.line_count(10), 1);
+            assert_eq!(text.get_height(10), 1);
+            assert_eq!(buffer, Buffer::with_lines(["          "]));
+        }
+    }
+
+    /// A field of hand-written input to deserialize a `Text` from, either a string or a list of
+    /// byte ranges.
+    #[cfg(feature = "serde")]
+    enum Field {
+        Str(&'static str),
+        Ranges(Vec<[usize; 2]>),
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserializer<'de> for Field {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V: serde::de::Visitor<'de>>(
+            self,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            use serde::de::value::SeqDeserializer;
+
+            match self {
+                Field::Str(value) => visitor.visit_str(value),
+                Field::Ranges(ranges) => serde::Deserializer::deserialize_any(
+                    SeqDeserializer::new(
+                        ranges
+                            .into_iter()
+                            .map(|range| SeqDeserializer::new(range.into_iter())),
+                    ),
+                    visitor,
+                ),
+            }
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+            option unit unit_struct newtype_struct seq tuple tuple_struct map struct identifier
+            ignored_any
+        }
+
+        fn deserialize_enum<V: serde::de::Visitor<'de>>(
+            self,
+            name: &'static str,
+            variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            match self {
+                Field::Str(value) => serde::Deserializer::deserialize_enum(
+                    serde::de::value::StrDeserializer::new(value),
+                    name,
+                    variants,
+                    visitor,
+                ),
+                ranges => ranges.deserialize_any(visitor),
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::de::IntoDeserializer<'de, serde::de::value::Error> for Field {
+        type Deserializer = Self;
+
+        fn into_deserializer(self) -> Self {
+            self
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn deserialize_text(fields: Vec<(&'static str, Field)>) -> Result<Text, String> {
+        use serde::de::value::MapDeserializer;
+
+        Text::deserialize(MapDeserializer::<_, serde::de::value::Error>::new(
+            fields.into_iter(),
+        ))
+        .map_err(|error| error.to_string())
     }
 
+    #[cfg(feature = "serde")]
     #[traced_test]
     #[test]
-    fn test_with_word_wrap_and_newlines() {
-        let text =
-            Text::from("Let's wrap this text that is long enough to do so.\nAnd it has a newline.");
-        let rect = Rect::new(0, 0, 40, 3);
-        let mut buffer = Buffer::empty(rect);
+    fn test_deserialize_defaults() {
+        let text = deserialize_text(vec![("text", Field::Str("Hello"))]).unwrap();
 
-        text.render_ref(rect, &mut buffer);
+        assert_eq!(
+            format!("{text:?}"),
+            format!("{:?}", Text::new("Hello".to_string()))
+        );
 
-        insta::assert_debug_snapshot!(buffer);
+        let text = deserialize_text(vec![
+            ("text", Field::Str("Hello")),
+            ("wrap", Field::Str("Truncate")),
+            ("highlights", Field::Ranges(vec![[1, 3]])),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            format!("{text:?}"),
+            format!(
+                "{:?}",
+                Text::new_with_wrap("Hello".to_string(), TextWrap::Truncate)
+                    .with_highlight("el".to_string(), Style::default())
+            )
+        );
     }
 
-    // TODO: Test with newlines and wrapping
+    #[cfg(feature = "serde")]
+    #[traced_test]
+    #[test]
+    fn test_deserialize_rejects_bad_ranges() {
+        for range in [[0, 6], [3, 2], [0, 1]] {
+            let error = deserialize_text(vec![
+                ("text", Field::Str("éllo")),
+                ("highlights", Field::Ranges(vec![range])),
+            ])
+            .unwrap_err();
+
+            assert_eq!(
+                error,
+                format!(
+                    "byte range {:?} isn't within the text or splits a character",
+                    range[0]..range[1]
+                )
+            );
+        }
+    }
 }
@@ -0,0 +1,231 @@
+//! Parsing of ANSI escape sequences, as output by programs writing coloured text to a terminal,
+//! into styled spans.
+
+use std::{iter::Peekable, str::Chars};
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+
+const COLORS: [Color; 8] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+];
+
+const BRIGHT_COLORS: [Color; 8] = [
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// Split the text into spans styled by any SGR (`ESC [ ... m`) sequences in it, the escape
+/// sequences themselves are removed. Any other escape sequences, including OSC ones such as
+/// window titles and hyperlinks, or SGR parameters we don't understand, are dropped.
+pub(super) fn parse_ansi(text: &str) -> Vec<Span<'static>> {
+    let mut spans = vec![];
+    let mut style = Style::default();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            current.push(c);
+            continue;
+        }
+
+        if !current.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut current), style));
+        }
+
+        if chars.next_if_eq(&']').is_some() {
+            skip_osc(&mut chars);
+            continue;
+        }
+
+        if chars.next_if_eq(&'[').is_none() {
+            // Not a control sequence, we just drop the escape and the character after it.
+            chars.next();
+            continue;
+        }
+
+        let mut params = String::new();
+        for c in chars.by_ref() {
+            if ('@'..='~').contains(&c) {
+                if c == 'm' {
+                    apply_sgr(&mut style, &params);
+                }
+                break;
+            }
+            params.push(c);
+        }
+    }
+
+    if !current.is_empty() {
+        spans.push(Span::styled(current, style));
+    }
+
+    spans
+}
+
+/// Skip the rest of an OSC (`ESC ] ...`) sequence, which ends with either BEL or ST (`ESC \`).
+fn skip_osc(chars: &mut Peekable<Chars<'_>>) {
+    while let Some(c) = chars.next() {
+        match c {
+            '\x07' => return,
+            '\x1b' if chars.next_if_eq(&'\\').is_some() => return,
+            _ => {}
+        }
+    }
+}
+
+/// Apply the parameters of an SGR sequence to the style. An empty parameter is the same as 0 and
+/// any that are out of range are ignored.
+fn apply_sgr(style: &mut Style, params: &str) {
+    let mut codes = params.split(';').map(|p| {
+        if p.is_empty() {
+            Some(0)
+        } else {
+            p.parse::<u8>().ok()
+        }
+    });
+
+    while let Some(code) = codes.next() {
+        let Some(code) = code else {
+            continue;
+        };
+        *style = match code {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            7 => style.add_modifier(Modifier::REVERSED),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(COLORS[(code - 30) as usize]),
+            38 => match extended_color(&mut codes) {
+                Some(color) => style.fg(color),
+                None => *style,
+            },
+            39 => style.fg(Color::Reset),
+            40..=47 => style.bg(COLORS[(code - 40) as usize]),
+            48 => match extended_color(&mut codes) {
+                Some(color) => style.bg(color),
+                None => *style,
+            },
+            49 => style.bg(Color::Reset),
+            90..=97 => style.fg(BRIGHT_COLORS[(code - 90) as usize]),
+            100..=107 => style.bg(BRIGHT_COLORS[(code - 100) as usize]),
+            _ => *style,
+        };
+    }
+}
+
+/// Parse the rest of a 256 colour (`5;n`) or RGB (`2;r;g;b`) colour parameter.
+fn extended_color(codes: &mut impl Iterator<Item = Option<u8>>) -> Option<Color> {
+    match codes.next()?? {
+        5 => Some(Color::Indexed(codes.next()??)),
+        2 => Some(Color::Rgb(codes.next()??, codes.next()??, codes.next()??)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_colors() {
+        let spans = parse_ansi("\x1b[31mred\x1b[0m normal");
+
+        assert_eq!(
+            spans,
+            vec![
+                Span::styled("red", Style::default().fg(Color::Red)),
+                Span::raw(" normal"),
+            ]
+        );
+        assert_eq!(spans[0].width(), 3);
+        assert_eq!(spans[1].width(), 7);
+    }
+
+    #[test]
+    fn test_parse_modifiers_and_background() {
+        let spans = parse_ansi("\x1b[1;4;44mbold\x1b[22m not bold\x1b[m");
+
+        assert_eq!(
+            spans,
+            vec![
+                Span::styled(
+                    "bold",
+                    Style::default()
+                        .bg(Color::Blue)
+                        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+                ),
+                Span::styled(
+                    " not bold",
+                    Style::default()
+                        .bg(Color::Blue)
+                        .add_modifier(Modifier::UNDERLINED)
+                        .remove_modifier(Modifier::BOLD | Modifier::DIM)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_sequences_dropped() {
+        let spans = parse_ansi("\x1b[2Kcleared\x1b[?25l \x1b[99mtext\x1b7");
+
+        assert_eq!(
+            spans,
+            vec![Span::raw("cleared"), Span::raw(" "), Span::raw("text"),]
+        );
+    }
+
+    #[test]
+    fn test_osc_sequences_dropped() {
+        let spans = parse_ansi(
+            "\x1b]0;window title\x07before \x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\ after",
+        );
+
+        assert_eq!(
+            spans,
+            vec![Span::raw("before "), Span::raw("link"), Span::raw(" after")]
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_parameters_ignored() {
+        let spans = parse_ansi("\x1b[31m\x1b[300mstill red\x1b[1;256;4m\x1b[38;5;999mbold");
+
+        assert_eq!(
+            spans,
+            vec![
+                Span::styled("still red", Style::default().fg(Color::Red)),
+                Span::styled(
+                    "bold",
+                    Style::default()
+                        .fg(Color::Red)
+                        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Code for a strotui TextInput.
+//!
+//! A TextInput is a single line of editable text with a cursor. When the value is wider than the
+//! area it's scrolled sideways to keep the cursor in view.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::WidgetRef,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use super::{Text, TextWrap};
+use crate::Handle;
+
+#[derive(Debug, Default)]
+pub struct TextInput {
+    value: String,
+    /// Byte index of the cursor into the value, always on a grapheme cluster boundary.
+    cursor: usize,
+    style: Style,
+}
+
+impl TextInput {
+    /// Creates an input holding `value` with the cursor at the end.
+    pub fn new(value: String) -> Self {
+        TextInput {
+            cursor: value.len(),
+            value,
+            style: Style::default(),
+        }
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The cursor position as a byte index into the value.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.value.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Deletes the grapheme cluster before the cursor, if there is one.
+    pub fn delete_backward(&mut self) {
+        let start = self.previous_boundary();
+        self.value.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.previous_boundary();
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = self.value[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |g| self.cursor + g.len());
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.value.len();
+    }
+
+    /// Edits the value or moves the cursor for the key, returning whether the key was used.
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.insert_char(c)
+            }
+            KeyCode::Backspace => self.delete_backward(),
+            KeyCode::Left => self.move_left(),
+            KeyCode::Right => self.move_right(),
+            KeyCode::Home => self.move_home(),
+            KeyCode::End => self.move_end(),
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn previous_boundary(&self) -> usize {
+        self.value[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// Byte index to start displaying the value from so the cursor fits within `width` columns,
+    /// along with the column the cursor is then displayed at.
+    fn scroll(&self, width: u16) -> (usize, u16) {
+        let width = width as usize;
+        let mut cursor_column = self.value[..self.cursor].width();
+        let mut start = 0;
+        for (i, g) in self.value[..self.cursor].grapheme_indices(true) {
+            // The cursor takes up a column of its own after the text before it.
+            if cursor_column < width {
+                break;
+            }
+            cursor_column -= g.width();
+            start = i + g.len();
+        }
+        (start, cursor_column as u16)
+    }
+}
+
+impl Handle for TextInput {
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.handle_key_event(key)
+    }
+}
+
+impl WidgetRef for TextInput {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        let (start, cursor_column) = self.scroll(area.width);
+        let text = Text::new_with_wrap(self.value[start..].to_string(), TextWrap::Truncate)
+            .with_style(self.style);
+        text.render_ref(Rect { height: 1, ..area }, buf);
+
+        let cursor_style = self.style.add_modifier(Modifier::REVERSED);
+        buf[(area.x + cursor_column, area.y)].set_style(cursor_style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tracing_test::traced_test;
+
+    #[traced_test]
+    #[test]
+    fn test_cursor_movement() {
+        let mut input = TextInput::new("héllo".to_string());
+        assert_eq!(input.cursor(), 6);
+
+        input.move_right();
+        assert_eq!(input.cursor(), 6);
+        input.move_left();
+        input.move_left();
+        input.move_left();
+        input.move_left();
+        assert_eq!(input.cursor(), 1);
+        input.move_home();
+        input.move_left();
+        assert_eq!(input.cursor(), 0);
+        input.move_right();
+        input.move_right();
+        assert_eq!(input.cursor(), 3);
+        input.move_end();
+        assert_eq!(input.cursor(), 6);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_insert_and_backspace() {
+        let mut input = TextInput::default();
+        input.delete_backward();
+        assert_eq!(input.value(), "");
+
+        for c in "hello".chars() {
+            input.insert_char(c);
+        }
+        input.move_home();
+        input.delete_backward();
+        assert_eq!(input.value(), "hello");
+
+        input.move_right();
+        input.delete_backward();
+        assert_eq!(input.value(), "ello");
+        assert_eq!(input.cursor(), 0);
+
+        input.move_end();
+        input.insert_char('!');
+        input.delete_backward();
+        input.delete_backward();
+        assert_eq!(input.value(), "ell");
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_key_events() {
+        let mut input = TextInput::default();
+
+        assert!(input.handle_key_event(KeyEvent::from(KeyCode::Char('a'))));
+        assert!(input.handle_key_event(KeyEvent::from(KeyCode::Char('b'))));
+        assert!(input.handle_key_event(KeyEvent::from(KeyCode::Left)));
+        assert!(input.handle_key_event(KeyEvent::from(KeyCode::Backspace)));
+        assert!(!input.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+        assert!(!input.handle_key_event(KeyEvent::from(KeyCode::Tab)));
+
+        assert_eq!(input.value(), "b");
+        assert_eq!(input.cursor(), 0);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_render_scrolled_to_cursor() {
+        let input = TextInput::new("Some text longer than the input".to_string());
+        let rect = Rect::new(0, 0, 12, 1);
+        let mut buffer = Buffer::empty(rect);
+
+        input.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_render_cursor_in_text() {
+        let mut input = TextInput::new("Some text".to_string());
+        input.move_home();
+        input.move_right();
+        let rect = Rect::new(0, 0, 12, 1);
+        let mut buffer = Buffer::empty(rect);
+
+        input.render_ref(rect, &mut buffer);
+
+        insta::assert_debug_snapshot!(buffer);
+    }
+}